@@ -0,0 +1,62 @@
+use aoc_2021::{
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+    day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn read_input(day: u8) -> String {
+    let path = format!("{}/input/2021/day{day}.txt", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(path).expect("Input file exists")
+}
+
+macro_rules! bench_day {
+    ($c:ident, $day:literal, $module:ident, [$($part:ident),+]) => {{
+        let input = read_input($day);
+        let gen = $module::generate(&input).expect("Input parses");
+
+        let mut group = $c.benchmark_group(stringify!($module));
+        group.sample_size(10);
+
+        group.bench_function("generator", |b| {
+            b.iter(|| $module::generate(black_box(&input)))
+        });
+        $(
+            group.bench_function(stringify!($part), |b| {
+                b.iter(|| $module::$part(black_box(&gen)))
+            });
+        )+
+
+        group.finish();
+    }};
+}
+
+fn days(c: &mut Criterion) {
+    bench_day!(c, 1, day01, [part1, part2]);
+    bench_day!(c, 2, day02, [part1, part2]);
+    bench_day!(c, 3, day03, [part1, part2]);
+    bench_day!(c, 4, day04, [part1, part2]);
+    bench_day!(c, 5, day05, [part1, part2]);
+    bench_day!(c, 6, day06, [part1, part2]);
+    bench_day!(c, 7, day07, [part1, part2]);
+    bench_day!(c, 8, day08, [part1, part2]);
+    bench_day!(c, 9, day09, [part1, part2]);
+    bench_day!(c, 10, day10, [part1, part2]);
+    bench_day!(c, 11, day11, [part1, part2]);
+    bench_day!(c, 12, day12, [part1, part2]);
+    bench_day!(c, 13, day13, [part1, part2]);
+    bench_day!(c, 14, day14, [part1, part2]);
+    bench_day!(c, 15, day15, [part1, part2]);
+    bench_day!(c, 16, day16, [part1, part2]);
+    bench_day!(c, 17, day17, [part1, part2]);
+    bench_day!(c, 18, day18, [part1, part2]);
+    bench_day!(c, 19, day19, [part1, part2]);
+    bench_day!(c, 20, day20, [part1, part2]);
+    bench_day!(c, 21, day21, [part1, part2]);
+    bench_day!(c, 22, day22, [part1, part2]);
+    bench_day!(c, 23, day23, [part1, part2]);
+    bench_day!(c, 24, day24, [part1, part2]);
+    bench_day!(c, 25, day25, [part1]);
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
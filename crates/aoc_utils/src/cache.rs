@@ -0,0 +1,178 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+
+use crate::solver::Answer;
+
+// Answers are stored one per file, named after the puzzle and a hash of the
+// solutions crate version and the input text, so any change to either misses.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    version: String,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>, version: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            version: version.into(),
+        }
+    }
+
+    fn path(&self, year: u16, day: u8, part: u8, input: &str) -> PathBuf {
+        let hash = fnv1a([self.version.as_bytes(), &[0], input.as_bytes()]);
+        self.dir
+            .join(format!("{year}-day{day:02}-part{part}-{hash:016x}"))
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8, input: &str) -> Option<Answer> {
+        let contents = fs::read_to_string(self.path(year, day, part, input)).ok()?;
+
+        match contents.split_once('\n')? {
+            ("number", n) => n.parse().ok().map(Answer::Number),
+            ("text", s) => Some(Answer::Text(s.to_string())),
+            _ => None,
+        }
+    }
+
+    pub fn put(
+        &self,
+        year: u16,
+        day: u8,
+        part: u8,
+        input: &str,
+        answer: &Answer,
+    ) -> io::Result<()> {
+        let contents = match answer {
+            Answer::Number(n) => format!("number\n{n}"),
+            Answer::Text(s) => format!("text\n{s}"),
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(year, day, part, input), contents)
+    }
+
+    // Removes every cached answer, returning how many there were. Other files in
+    // the directory are left alone.
+    pub fn clear(&self) -> io::Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            let is_answer = path
+                .file_name()
+                .and_then(|it| it.to_str())
+                .is_some_and(is_cache_file);
+            if is_answer && path.is_file() {
+                fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+// Whether `name` matches the `{year}-day{NN}-part{N}-{hash}` names `path` writes.
+fn is_cache_file(name: &str) -> bool {
+    let digits = |s: &str, min_len| s.len() >= min_len && s.bytes().all(|b| b.is_ascii_digit());
+
+    let fields = name.split('-').collect::<Vec<_>>();
+    let [year, day, part, hash] = fields[..] else {
+        return false;
+    };
+
+    digits(year, 1)
+        && day.strip_prefix("day").is_some_and(|it| digits(it, 2))
+        && part.strip_prefix("part").is_some_and(|it| digits(it, 1))
+        && hash.len() == 16
+        && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+// FNV-1a, chosen over `DefaultHasher` because it is stable across Rust releases.
+fn fnv1a<const N: usize>(chunks: [&[u8]; N]) -> u64 {
+    chunks
+        .iter()
+        .flat_map(|it| it.iter())
+        .fold(0xCBF2_9CE4_8422_2325, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str, version: &str) -> Cache {
+        let dir = std::env::temp_dir().join(format!("aoc_utils-{name}-{}", std::process::id()));
+        Cache::new(dir, version)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cache = temp_cache("round-trip", "0.1.0");
+        let text = Answer::Text(String::from("#..#\n####"));
+
+        assert_eq!(cache.get(2021, 1, 1, "199\n200"), None);
+        assert!(cache
+            .put(2021, 1, 1, "199\n200", &Answer::Number(7))
+            .is_ok());
+        assert!(cache.put(2021, 13, 2, "6,10", &text).is_ok());
+
+        assert_eq!(cache.get(2021, 1, 1, "199\n200"), Some(Answer::Number(7)));
+        assert_eq!(cache.get(2021, 13, 2, "6,10"), Some(text));
+        assert_eq!(cache.get(2021, 1, 2, "199\n200"), None);
+        assert_eq!(cache.get(2021, 1, 1, "199\n201"), None);
+        assert_eq!(
+            temp_cache("round-trip", "0.2.0").get(2021, 1, 1, "199\n200"),
+            None
+        );
+
+        assert_eq!(cache.clear().ok(), Some(2));
+        assert_eq!(cache.get(2021, 1, 1, "199\n200"), None);
+        assert_eq!(cache.clear().ok(), Some(0));
+    }
+
+    #[test]
+    fn test_clear_keeps_foreign_files() {
+        let cache = temp_cache("foreign", "0.1.0");
+        assert!(cache.put(2021, 6, 1, "3,4", &Answer::Number(5)).is_ok());
+
+        let foreign = [
+            "notes.txt",
+            "2021-day06-part1-not-a-hash",
+            "2021-day06-part1",
+        ];
+        for name in foreign {
+            assert!(fs::write(cache.dir.join(name), "keep me").is_ok());
+        }
+
+        assert_eq!(cache.clear().ok(), Some(1));
+        for name in foreign {
+            assert!(cache.dir.join(name).is_file(), "{name} was removed");
+        }
+        assert!(fs::remove_dir_all(&cache.dir).is_ok());
+    }
+
+    #[test]
+    fn test_is_cache_file() {
+        assert!(is_cache_file("2021-day06-part1-0123456789abcdef"));
+        assert!(!is_cache_file("2021-day06-part1-0123456789ABCDEF"));
+        assert!(!is_cache_file("2021-day6-part1-0123456789abcdef"));
+        assert!(!is_cache_file("2021-day06-part1-0123456789abcdef.bak"));
+        assert!(!is_cache_file(".bashrc"));
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a([]), 0xCBF2_9CE4_8422_2325);
+        assert_eq!(fnv1a([b"a"]), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(fnv1a([b"fo", b"obar"]), fnv1a([b"foobar"]));
+    }
+}
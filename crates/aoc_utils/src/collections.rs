@@ -0,0 +1,6 @@
+// Solvers that also build without `std` fall back to an ordered map, so their
+// keys need both `Hash` and `Ord`.
+#[cfg(not(feature = "std"))]
+pub use alloc::collections::BTreeMap as Map;
+#[cfg(feature = "std")]
+pub use std::collections::HashMap as Map;
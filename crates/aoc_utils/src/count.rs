@@ -0,0 +1,132 @@
+use core::fmt;
+
+// Counters that grow exponentially with a puzzle's parameters. With the
+// `bigint` feature they are exact, otherwise every operation is checked.
+#[cfg(feature = "bigint")]
+type Repr = num_bigint::BigUint;
+#[cfg(not(feature = "bigint"))]
+type Repr = u64;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Count(Repr);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("arithmetic overflow, try the `bigint` feature")
+    }
+}
+
+impl core::error::Error for Overflow {}
+
+impl Count {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn checked_add(&self, other: &Self) -> Result<Self, Overflow> {
+        Ok(Self(&self.0 + &other.0))
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    pub fn checked_add(&self, other: &Self) -> Result<Self, Overflow> {
+        self.0.checked_add(other.0).map(Self).ok_or(Overflow)
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, Overflow> {
+        if self.0 < other.0 {
+            return Err(Overflow);
+        }
+        Ok(Self(&self.0 - &other.0))
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, Overflow> {
+        self.0.checked_sub(other.0).map(Self).ok_or(Overflow)
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, Overflow> {
+        Ok(Self(&self.0 * &other.0))
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, Overflow> {
+        self.0.checked_mul(other.0).map(Self).ok_or(Overflow)
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn to_u64(&self) -> Option<u64> {
+        u64::try_from(&self.0).ok()
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    pub const fn to_u64(&self) -> Option<u64> {
+        Some(self.0)
+    }
+
+    pub fn sum<'a>(counts: impl IntoIterator<Item = &'a Self>) -> Result<Self, Overflow> {
+        counts
+            .into_iter()
+            .try_fold(Self::zero(), |acc, it| acc.checked_add(it))
+    }
+}
+
+impl From<u64> for Count {
+    #[cfg(feature = "bigint")]
+    fn from(n: u64) -> Self {
+        Self(n.into())
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    fn from(n: u64) -> Self {
+        Self(n)
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let (two, three) = (Count::from(2), Count::from(3));
+        assert_eq!(two.checked_add(&three), Ok(Count::from(5)));
+        assert_eq!(three.checked_sub(&two), Ok(Count::from(1)));
+        assert_eq!(two.checked_sub(&three), Err(Overflow));
+        assert_eq!(two.checked_mul(&three), Ok(Count::from(6)));
+        assert_eq!(Count::sum([&two, &three, &two]), Ok(Count::from(7)));
+        assert_eq!(Count::from(7).to_string(), "7");
+    }
+
+    #[test]
+    fn test_overflow() {
+        let max = Count::from(u64::MAX);
+        let res = max.checked_add(&Count::from(1));
+        let squared = max.checked_mul(&max);
+
+        if cfg!(feature = "bigint") {
+            assert_eq!(
+                res.map(|it| it.to_string()).ok().as_deref(),
+                Some("18446744073709551616")
+            );
+            assert_eq!(
+                squared.map(|it| it.to_string()).ok().as_deref(),
+                Some("340282366920938463426481119284349108225")
+            );
+        } else {
+            assert_eq!(res, Err(Overflow));
+            assert_eq!(squared, Err(Overflow));
+        }
+    }
+}
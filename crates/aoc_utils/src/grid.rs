@@ -0,0 +1,329 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+
+use crate::parse::ParseError;
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// Positions are `(x, y)` pairs, `x` being the column and `y` the row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        (cells.len() == width * height).then_some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut((usize, usize)) -> T,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_lines<'a, I, F>(day: u8, lines: I, f: F, expected: &str) -> Result<Self, ParseError>
+    where
+        I: IntoIterator<Item = (usize, &'a str)>,
+        F: Fn(char) -> Option<T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        let mut last_line = 0;
+
+        for (idx, line) in lines {
+            last_line = idx;
+
+            let line_width = line.chars().count();
+            let row_width = *width.get_or_insert(line_width);
+            if row_width == 0 {
+                return Err(ParseError::new(day, idx + 1, 1, expected));
+            }
+
+            if line_width != row_width {
+                return Err(ParseError::new(
+                    day,
+                    idx + 1,
+                    line_width.min(row_width) + 1,
+                    format!("a row of width {row_width}"),
+                ));
+            }
+
+            for (column, c) in line.chars().enumerate() {
+                let cell =
+                    f(c).ok_or_else(|| ParseError::new(day, idx + 1, column + 1, expected))?;
+                cells.push(cell);
+            }
+
+            height += 1;
+        }
+
+        let Some(width) = width else {
+            return Err(ParseError::new(day, last_line + 1, 1, expected));
+        };
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn parse<F>(day: u8, inp: &str, f: F, expected: &str) -> Result<Self, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        Self::from_lines(day, inp.lines().enumerate(), f, expected)
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn offset(&self, pos: (usize, usize), delta: (isize, isize)) -> Option<(usize, usize)> {
+        bounded_offset(pos, delta, self.width, self.height)
+    }
+
+    pub const fn wrapping_offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> (usize, usize) {
+        (wrap(x, dx, self.width), wrap(y, dy, self.height))
+    }
+
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        NEIGHBOURS_4
+            .into_iter()
+            .filter_map(move |d| bounded_offset(pos, d, width, height))
+    }
+
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        NEIGHBOURS_8
+            .into_iter()
+            .filter_map(move |d| bounded_offset(pos, d, width, height))
+    }
+
+    pub fn wrapping_neighbours4(
+        &self,
+        pos: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        NEIGHBOURS_4
+            .into_iter()
+            .map(move |(dx, dy)| (wrap(pos.0, dx, width), wrap(pos.1, dy, height)))
+    }
+
+    pub fn wrapping_neighbours8(
+        &self,
+        pos: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        NEIGHBOURS_8
+            .into_iter()
+            .map(move |(dx, dy)| (wrap(pos.0, dx, width), wrap(pos.1, dy, height)))
+    }
+
+    pub fn render<F>(&self, f: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut result = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width.max(1)) {
+            if !result.is_empty() {
+                result.push('\n');
+            }
+            result.extend(row.iter().map(&f));
+        }
+
+        result
+    }
+}
+
+impl Grid<usize> {
+    pub fn parse_digits(day: u8, inp: &str) -> Result<Self, ParseError> {
+        Self::parse(day, inp, |c| c.to_digit(10).map(|d| d as usize), "a digit")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Grid<T> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        self.get(pos).expect("Position is inside the grid")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        self.get_mut(pos).expect("Position is inside the grid")
+    }
+}
+
+fn bounded_offset(
+    (x, y): (usize, usize),
+    (dx, dy): (isize, isize),
+    width: usize,
+    height: usize,
+) -> Option<(usize, usize)> {
+    let x = x.checked_add_signed(dx).filter(|&x| x < width)?;
+    let y = y.checked_add_signed(dy).filter(|&y| y < height)?;
+    Some((x, y))
+}
+
+const fn wrap(pos: usize, delta: isize, len: usize) -> usize {
+    (pos.cast_signed() + delta).rem_euclid(len.cast_signed()) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &str = "123\n\
+                             456";
+
+    #[test]
+    fn test_parse_and_render() {
+        let Ok(grid) = Grid::parse_digits(1, TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[(0, 1)], 4);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.render(|d| char::from(b'0' + *d as u8)), TEST_DATA);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let res = Grid::parse_digits(9, "219\n3x8");
+        assert_eq!(res, Err(ParseError::new(9, 2, 2, "a digit")));
+
+        let res = Grid::parse_digits(9, "219\n39");
+        assert_eq!(res, Err(ParseError::new(9, 2, 3, "a row of width 3")));
+
+        let res = Grid::parse_digits(9, "219\n3987");
+        assert_eq!(res, Err(ParseError::new(9, 2, 4, "a row of width 3")));
+
+        let res = Grid::parse_digits(9, "");
+        assert_eq!(res, Err(ParseError::new(9, 1, 1, "a digit")));
+    }
+
+    #[test]
+    fn test_bounded_neighbours() {
+        let grid = Grid::from_fn(3, 3, |_| 0);
+
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 2)).count(), 5);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_wrapping_neighbours() {
+        let grid = Grid::from_fn(3, 2, |_| 0);
+
+        assert_eq!(grid.wrapping_offset((2, 1), (1, 1)), (0, 0));
+        assert_eq!(grid.wrapping_offset((0, 0), (-1, -1)), (2, 1));
+
+        let neighbours = grid.wrapping_neighbours4((0, 0)).collect::<Vec<_>>();
+        assert_eq!(neighbours, vec![(0, 1), (2, 0), (1, 0), (0, 1)]);
+        assert_eq!(grid.wrapping_neighbours8((1, 1)).count(), 8);
+    }
+}
@@ -0,0 +1,216 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::count::Count;
+use crate::vector::{Vec2, Vec3};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(i128),
+    Text(String),
+    List(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+
+pub fn string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(result, "\\u{:04x}", u32::from(c));
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+
+    result
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Text(s) => f.write_str(&string(s)),
+            Self::List(items) => {
+                f.write_char('[')?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_char(']')
+            }
+            Self::Object(fields) => {
+                f.write_char('{')?;
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}:{value}", string(key))?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(n: $t) -> Self {
+                    Self::Number(i128::from(n))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Self::Number(n as i128)
+    }
+}
+
+impl From<isize> for Value {
+    fn from(n: isize) -> Self {
+        Self::Number(n as i128)
+    }
+}
+
+impl From<Count> for Value {
+    fn from(count: Count) -> Self {
+        count
+            .to_u64()
+            .map_or_else(|| Self::Text(count.to_string()), Self::from)
+    }
+}
+
+impl From<char> for Value {
+    fn from(c: char) -> Self {
+        Self::Text(c.to_string())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl<T: Clone + Into<Self>> From<&[T]> for Value {
+    fn from(items: &[T]) -> Self {
+        Self::List(items.iter().cloned().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Self>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Self::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<K: ToString, V: Into<Self>> From<BTreeMap<K, V>> for Value {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        Self::Object(
+            map.into_iter()
+                .map(|(k, v)| (k.to_string(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+// Keys are sorted so that traces of the same input are identical.
+#[cfg(feature = "std")]
+impl<K: ToString, V: Into<Self>> From<HashMap<K, V>> for Value {
+    fn from(map: HashMap<K, V>) -> Self {
+        let mut fields = map
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.into()))
+            .collect::<Vec<_>>();
+        fields.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        Self::Object(fields)
+    }
+}
+
+impl<A: Into<Self>, B: Into<Self>> From<(A, B)> for Value {
+    fn from((a, b): (A, B)) -> Self {
+        Self::List(vec![a.into(), b.into()])
+    }
+}
+
+impl<T: Into<Self>> From<Vec2<T>> for Value {
+    fn from(v: Vec2<T>) -> Self {
+        Self::List(vec![v.x.into(), v.y.into()])
+    }
+}
+
+impl<T: Into<Self>> From<Vec3<T>> for Value {
+    fn from(v: Vec3<T>) -> Self {
+        Self::List(vec![v.x.into(), v.y.into(), v.z.into()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let value = Value::Object(vec![
+            (String::from("name"), Value::from("a \"b\"\n")),
+            (String::from("pos"), Value::from(Vec2::new(-1i64, 2))),
+            (String::from("big"), Value::from(u64::MAX)),
+            (String::from("done"), Value::from(true)),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"name":"a \"b\"\n","pos":[-1,2],"big":18446744073709551615,"done":true}"#
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_maps_are_sorted() {
+        let map = HashMap::from([("b", 2usize), ("a", 1), ("c", 3)]);
+        assert_eq!(Value::from(map).to_string(), r#"{"a":1,"b":2,"c":3}"#);
+    }
+}
@@ -0,0 +1,35 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![deny(unsafe_code)]
+#![warn(nonstandard_style, rust_2018_idioms)]
+#![allow(
+    clippy::implicit_hasher,
+    clippy::similar_names,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::must_use_candidate,
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc
+)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod collections;
+pub mod count;
+pub mod grid;
+pub mod json;
+pub mod parse;
+pub mod rng;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "std")]
+pub mod solver;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod trace;
+pub mod vector;
+
+pub use parse::ParseError;
+#[cfg(feature = "std")]
+pub use solver::{Answer, Error, Puzzle};
@@ -0,0 +1,144 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, expected: impl Into<String>) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            expected: expected.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+// Strips a byte order mark, CRLF line endings, trailing whitespace on each line
+// and trailing blank lines, so generators only ever see `\n`-separated lines.
+pub fn normalise(inp: &str) -> String {
+    let mut result = String::with_capacity(inp.len());
+    for (idx, line) in inp.lines().enumerate() {
+        result.push_str(normalise_line(idx, line));
+        result.push('\n');
+    }
+
+    let len = result.trim_end_matches('\n').len();
+    result.truncate(len);
+    result
+}
+
+// A single line of input as `normalise` leaves it, given its zero-based index.
+pub fn normalise_line(idx: usize, line: &str) -> &str {
+    let line = if idx == 0 {
+        line.strip_prefix('\u{feff}').unwrap_or(line)
+    } else {
+        line
+    };
+
+    line.trim_end()
+}
+
+/// 1-based column of `token`, which must be a sub-slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+    line[..offset.min(line.len())].chars().count() + 1
+}
+
+pub fn first_line<'a>(day: u8, inp: &'a str, expected: &str) -> Result<&'a str, ParseError> {
+    inp.lines()
+        .next()
+        .filter(|it| !it.is_empty())
+        .ok_or_else(|| ParseError::new(day, 1, 1, expected))
+}
+
+pub fn parse_line<T: FromStr>(
+    day: u8,
+    idx: usize,
+    line: &str,
+    expected: &str,
+) -> Result<T, ParseError> {
+    line.parse()
+        .map_err(|_| ParseError::new(day, idx + 1, 1, expected))
+}
+
+pub fn parse_lines<T: FromStr>(day: u8, inp: &str, expected: &str) -> Result<Vec<T>, ParseError> {
+    inp.lines()
+        .enumerate()
+        .map(|(idx, line)| parse_line(day, idx, line, expected))
+        .collect()
+}
+
+pub fn parse_list<T: FromStr>(
+    day: u8,
+    line_no: usize,
+    line: &str,
+    sep: char,
+    expected: &str,
+) -> Result<Vec<T>, ParseError> {
+    line.split(sep)
+        .map(|token| {
+            token
+                .trim()
+                .parse()
+                .map_err(|_| ParseError::new(day, line_no, column_of(line, token), expected))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let res = parse_lines::<u64>(1, "1\n2\nx\n4", "a number");
+        assert_eq!(res, Err(ParseError::new(1, 3, 1, "a number")));
+
+        let res = parse_lines::<u64>(1, "1\n2\n3", "a number");
+        assert_eq!(res, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_parse_list_reports_column() {
+        let res = parse_list::<u64>(6, 1, "3,4,x,1", ',', "a timer");
+        assert_eq!(res, Err(ParseError::new(6, 1, 5, "a timer")));
+    }
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(normalise("1\n\n2\n3"), "1\n\n2\n3");
+        assert_eq!(normalise("\u{feff}1 \r\n\t\r\n2\r\n3\r\n\r\n"), "1\n\n2\n3");
+        assert_eq!(normalise("  #A#\n  ###  \n"), "  #A#\n  ###");
+        assert_eq!(normalise("\u{feff}"), "");
+    }
+
+    #[test]
+    fn test_display() {
+        let err = ParseError::new(14, 1, 1, "a polymer template");
+        assert_eq!(
+            err.to_string(),
+            "day 14, line 1, column 1: expected a polymer template"
+        );
+    }
+}
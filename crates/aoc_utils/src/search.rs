@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::grid::Grid;
+
+// Every node reachable from `start`, including `start` itself.
+pub fn flood_fill<N, F, I>(start: N, mut neighbours: F) -> HashSet<N>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = vec![start];
+
+    while let Some(node) = queue.pop() {
+        for next in neighbours(&node) {
+            if seen.insert(next.clone()) {
+                queue.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+pub fn shortest_path<N, FN, IN, FS>(
+    start: &N,
+    successors: FN,
+    success: FS,
+) -> Option<(Vec<N>, usize)>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FS: FnMut(&N) -> bool,
+{
+    pathfinding::prelude::dijkstra(start, successors, success)
+}
+
+// Cheapest 4-connected walk where entering a cell costs its value.
+pub fn grid_path_cost(
+    grid: &Grid<usize>,
+    start: (usize, usize),
+    target: (usize, usize),
+) -> Option<usize> {
+    let (_, cost) = shortest_path(
+        &start,
+        |&pos| grid.neighbours4(pos).map(|it| (it, grid[it])),
+        |&pos| pos == target,
+    )?;
+
+    Some(cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flood_fill() {
+        let Ok(grid) = Grid::parse_digits(9, "2199\n3989\n9856") else {
+            panic!("Could not parse test grid")
+        };
+
+        let basin = flood_fill((0, 0), |&pos| {
+            grid.neighbours4(pos)
+                .filter(|&it| grid[it] != 9)
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(basin.len(), 3);
+    }
+
+    #[test]
+    fn test_grid_path_cost() {
+        let Ok(grid) = Grid::parse_digits(15, "116\n138\n213") else {
+            panic!("Could not parse test grid")
+        };
+
+        assert_eq!(grid_path_cost(&grid, (0, 0), (2, 2)), Some(7));
+        assert_eq!(grid_path_cost(&grid, (0, 0), (0, 0)), Some(0));
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt;
+
+use crate::count::{Count, Overflow};
+use crate::parse::{self, ParseError};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+// Why a part's result could not be turned into an answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unanswered {
+    Missing,
+    Overflow,
+    // A reason of the puzzle's own, for failures the others do not describe.
+    Failed(&'static str),
+}
+
+impl fmt::Display for Unanswered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("input has no answer"),
+            Self::Overflow => Overflow.fmt(f),
+            Self::Failed(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for Unanswered {}
+
+impl From<Overflow> for Unanswered {
+    fn from(Overflow: Overflow) -> Self {
+        Self::Overflow
+    }
+}
+
+impl Unanswered {
+    pub const fn into_error(self, day: u8, part: u8) -> Error {
+        match self {
+            Self::Missing => Error::NoAnswer { day, part },
+            Self::Overflow => Error::Overflow { day, part },
+            Self::Failed(reason) => Error::Failed { day, part, reason },
+        }
+    }
+}
+
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, Unanswered>;
+}
+
+macro_rules! impl_into_answer_for_number {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAnswer for $t {
+                fn into_answer(self) -> Result<Answer, Unanswered> {
+                    i64::try_from(self)
+                        .map(Answer::Number)
+                        .map_err(|_| Unanswered::Overflow)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_answer_for_number!(usize, u64, i64);
+
+// Counts too large for a number are only possible with `bigint`, and are
+// answered in decimal text.
+impl IntoAnswer for Count {
+    fn into_answer(self) -> Result<Answer, Unanswered> {
+        match self.to_u64().and_then(|it| i64::try_from(it).ok()) {
+            Some(n) => Ok(Answer::Number(n)),
+            None if cfg!(feature = "bigint") => Ok(Answer::Text(self.to_string())),
+            None => Err(Unanswered::Overflow),
+        }
+    }
+}
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Result<Answer, Unanswered> {
+        Ok(Answer::Text(self))
+    }
+}
+
+impl<T: IntoAnswer> IntoAnswer for Option<T> {
+    fn into_answer(self) -> Result<Answer, Unanswered> {
+        self.ok_or(Unanswered::Missing)?.into_answer()
+    }
+}
+
+impl<T: IntoAnswer, E: Into<Unanswered>> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Result<Answer, Unanswered> {
+        self.map_err(Into::into)?.into_answer()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    NoAnswer {
+        day: u8,
+        part: u8,
+    },
+    Overflow {
+        day: u8,
+        part: u8,
+    },
+    Failed {
+        day: u8,
+        part: u8,
+        reason: &'static str,
+    },
+    UnknownPuzzle {
+        year: u16,
+        day: u8,
+        part: u8,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{err}"),
+            Self::NoAnswer { day, part } => {
+                write!(f, "day {day}, part {part}: input has no answer")
+            }
+            Self::Overflow { day, part } => {
+                write!(f, "day {day}, part {part}: {Overflow}")
+            }
+            Self::Failed { day, part, reason } => {
+                write!(f, "day {day}, part {part}: {reason}")
+            }
+            Self::UnknownPuzzle { year, day, part } => {
+                write!(f, "no solution for {year} day {day}, part {part}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    parser: fn(&str) -> Result<(), ParseError>,
+    solver: fn(&str) -> Result<Answer, Error>,
+}
+
+impl Puzzle {
+    pub const fn new(
+        year: u16,
+        day: u8,
+        part: u8,
+        parser: fn(&str) -> Result<(), ParseError>,
+        solver: fn(&str) -> Result<Answer, Error>,
+    ) -> Self {
+        Self {
+            year,
+            day,
+            part,
+            parser,
+            solver,
+        }
+    }
+
+    // Runs only the generator, never the solution itself.
+    pub fn parse(&self, input: &str) -> Result<(), ParseError> {
+        (self.parser)(&parse::normalise(input))
+    }
+
+    pub fn solve(&self, input: &str) -> Result<Answer, Error> {
+        (self.solver)(&parse::normalise(input))
+    }
+}
+
+impl fmt::Debug for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Puzzle")
+            .field("year", &self.year)
+            .field("day", &self.day)
+            .field("part", &self.part)
+            .finish_non_exhaustive()
+    }
+}
+
+// Builds a year's registry from its day modules, which must sit at the crate
+// root and expose `generate` plus one function per part.
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! puzzles {
+    (
+        year = $year:literal;
+        $($day:literal => $module:ident [$($part:ident = $part_no:literal),+]),* $(,)?
+    ) => {
+        &[$($(
+            $crate::solver::Puzzle::new(
+                $year,
+                $day,
+                $part_no,
+                |input| crate::$module::generate(input).map(drop),
+                |input| {
+                    let gen = crate::$module::generate(input)?;
+                    $crate::solver::IntoAnswer::into_answer(crate::$module::$part(&gen))
+                        .map_err(|it| it.into_error($day, $part_no))
+                },
+            ),
+        )+)*]
+    };
+}
@@ -0,0 +1,144 @@
+use std::io::{self, BufRead};
+use std::{fmt, iter};
+
+use crate::count::Overflow;
+use crate::parse::{self, ParseError};
+use crate::solver::Unanswered;
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+    Unanswered(Unanswered),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read input: {err}"),
+            Self::Parse(err) => err.fmt(f),
+            Self::Unanswered(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::Unanswered(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<Unanswered> for StreamError {
+    fn from(err: Unanswered) -> Self {
+        Self::Unanswered(err)
+    }
+}
+
+impl From<Overflow> for StreamError {
+    fn from(err: Overflow) -> Self {
+        Self::Unanswered(err.into())
+    }
+}
+
+// Zero-based line numbers paired with each line, mirroring `str::lines().enumerate()`.
+// Lines are normalised one at a time, like `parse::normalise` does for whole inputs.
+// Blank lines are held back until a non-blank one follows, so trailing ones are
+// dropped as well.
+pub fn lines<R: BufRead>(reader: R) -> impl Iterator<Item = Result<(usize, String), StreamError>> {
+    let mut lines = reader.lines().enumerate();
+    let mut blanks = 0..0;
+    let mut held = None;
+
+    iter::from_fn(move || {
+        if let Some(idx) = blanks.next() {
+            return Some(Ok((idx, String::new())));
+        }
+        if let Some(line) = held.take() {
+            return Some(Ok(line));
+        }
+
+        loop {
+            let (idx, line) = lines.next()?;
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+
+            let line = parse::normalise_line(idx, &line);
+            if line.is_empty() {
+                if blanks.is_empty() {
+                    blanks.start = idx;
+                }
+                blanks.end = idx + 1;
+                continue;
+            }
+
+            let line = (idx, line.to_string());
+            let Some(idx) = blanks.next() else {
+                return Some(Ok(line));
+            };
+            held = Some(line);
+            return Some(Ok((idx, String::new())));
+        }
+    })
+}
+
+pub fn parse_lines<R, T, F>(reader: R, mut f: F) -> impl Iterator<Item = Result<T, StreamError>>
+where
+    R: BufRead,
+    F: FnMut(usize, &str) -> Result<T, ParseError>,
+{
+    lines(reader).map(move |line| {
+        let (idx, line) = line?;
+        Ok(f(idx, &line)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_line;
+
+    #[test]
+    fn test_parse_lines() {
+        let res = parse_lines("\u{feff}1\n2 \r\nx\n".as_bytes(), |idx, line| {
+            parse_line::<u64>(1, idx, line, "a number")
+        })
+        .collect::<Vec<_>>();
+
+        assert_eq!(res.len(), 3);
+        assert!(matches!(res[1], Ok(2)));
+        assert!(matches!(
+            &res[2],
+            Err(StreamError::Parse(err)) if *err == ParseError::new(1, 3, 1, "a number")
+        ));
+    }
+
+    #[test]
+    fn test_trailing_blank_lines_are_dropped() {
+        let res = lines(&b"1\n\n \n2\r\n\n  \r\n\n"[..])
+            .map(Result::ok)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            res,
+            [(0, "1"), (1, ""), (2, ""), (3, "2")].map(|(idx, line)| Some((idx, line.to_string())))
+        );
+    }
+}
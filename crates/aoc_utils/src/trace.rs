@@ -0,0 +1,153 @@
+#[cfg(feature = "std")]
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use crate::json::{self, Value};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub day: u8,
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, Value)>,
+}
+
+impl Event {
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find_map(|(key, value)| (*key == name).then_some(value))
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{\"day\":{},\"event\":{}",
+            self.day,
+            json::string(self.name)
+        )?;
+        for (key, value) in &self.fields {
+            write!(f, ",{}:{value}", json::string(key))?;
+        }
+        f.write_str("}")
+    }
+}
+
+pub trait Subscriber {
+    fn event(&self, event: &Event);
+}
+
+#[derive(Debug, Default)]
+pub struct Collector {
+    events: RefCell<Vec<Event>>,
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        self.events.borrow().clone()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn write_json_lines(&self, mut out: impl Write) -> io::Result<()> {
+        for event in self.events.borrow().iter() {
+            writeln!(out, "{event}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Subscriber for Collector {
+    fn event(&self, event: &Event) {
+        self.events.borrow_mut().push(event.clone());
+    }
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static SUBSCRIBER: RefCell<Option<Rc<dyn Subscriber>>> = RefCell::new(None);
+}
+
+#[cfg(feature = "std")]
+struct Restore(Option<Rc<dyn Subscriber>>);
+
+#[cfg(feature = "std")]
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        SUBSCRIBER.with(|it| *it.borrow_mut() = previous);
+    }
+}
+
+// Events are only seen by the thread that installed the subscriber, so work
+// split across rayon workers goes untraced.
+#[cfg(feature = "std")]
+pub fn with_subscriber<R>(subscriber: Rc<dyn Subscriber>, f: impl FnOnce() -> R) -> R {
+    let previous = SUBSCRIBER.with(|it| it.borrow_mut().replace(subscriber));
+    let _restore = Restore(previous);
+    f()
+}
+
+#[cfg(feature = "std")]
+pub fn emit(day: u8, name: &'static str, fields: impl FnOnce() -> Vec<(&'static str, Value)>) {
+    let subscriber = SUBSCRIBER.with(|it| it.borrow().clone());
+    if let Some(subscriber) = subscriber {
+        subscriber.event(&Event {
+            day,
+            name,
+            fields: fields(),
+        });
+    }
+}
+
+// Without `std` there is no thread-local to install a subscriber in, so
+// events are dropped.
+#[cfg(not(feature = "std"))]
+pub fn emit(_day: u8, _name: &'static str, _fields: impl FnOnce() -> Vec<(&'static str, Value)>) {}
+
+// `cfg!` is evaluated in the calling crate, so each solutions crate decides
+// through its own `trace` feature whether events are emitted at all.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! trace_event {
+    ($day:literal, $name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        if cfg!(feature = "trace") {
+            $crate::trace::emit($day, $name, || {
+                [$((stringify!($field), $crate::json::Value::from($value))),*].into()
+            });
+        }
+    };
+}
+
+pub use trace_event as event;
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_and_dump() {
+        let collector = Rc::new(Collector::new());
+        with_subscriber(collector.clone(), || {
+            emit(14, "step", || vec![("step", Value::from(1u8))]);
+            emit(14, "done", Vec::new);
+        });
+        emit(14, "ignored", Vec::new);
+
+        let mut out = Vec::new();
+        assert!(collector.write_json_lines(&mut out).is_ok());
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "{\"day\":14,\"event\":\"step\",\"step\":1}\n{\"day\":14,\"event\":\"done\"}\n"
+        );
+    }
+}
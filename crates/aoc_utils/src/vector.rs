@@ -0,0 +1,288 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use core::str::FromStr;
+
+pub trait Scalar: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    #[must_use]
+    fn distance(self, other: Self) -> Self;
+}
+
+pub trait Signed: Scalar + Neg<Output = Self> {
+    #[must_use]
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                fn distance(self, other: Self) -> Self {
+                    (self - other).abs()
+                }
+            }
+
+            impl Signed for $t {
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                fn distance(self, other: Self) -> Self {
+                    self.abs_diff(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_signed!(i32, i64, isize);
+impl_unsigned!(u32, u64, usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseVectorError {
+    pub input: String,
+}
+
+impl fmt::Display for ParseVectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid vector: '{}'", self.input)
+    }
+}
+
+impl core::error::Error for ParseVectorError {}
+
+fn parse_components<T: FromStr, const N: usize>(s: &str) -> Result<[T; N], ParseVectorError> {
+    let error = || ParseVectorError {
+        input: s.to_string(),
+    };
+
+    let components = s
+        .split(',')
+        .map(|it| it.trim().parse::<T>().map_err(|_| error()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    components.try_into().map_err(|_| error())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Scalar> Vec2<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+}
+
+impl<T: Signed> Vec2<T> {
+    #[must_use]
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Scalar> Vec3<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y) + self.z.distance(other.z)
+    }
+}
+
+impl<T: Signed> Vec3<T> {
+    #[must_use]
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    // One of the 24 orientations reachable by axis-aligned rotations, `num` in `0..24`.
+    #[must_use]
+    pub fn rotate(self, num: usize) -> Self {
+        let Self { x, y, z } = self;
+
+        match num {
+            0 => Self::new(x, y, z),
+            1 => Self::new(x, z, -y),
+            2 => Self::new(x, -y, -z),
+            3 => Self::new(x, -z, y),
+            4 => Self::new(y, x, -z),
+            5 => Self::new(y, z, x),
+            6 => Self::new(y, -x, z),
+            7 => Self::new(y, -z, -x),
+            8 => Self::new(z, x, y),
+            9 => Self::new(z, y, -x),
+            10 => Self::new(z, -x, -y),
+            11 => Self::new(z, -y, x),
+            12 => Self::new(-x, y, -z),
+            13 => Self::new(-x, z, y),
+            14 => Self::new(-x, -y, z),
+            15 => Self::new(-x, -z, -y),
+            16 => Self::new(-y, x, z),
+            17 => Self::new(-y, z, -x),
+            18 => Self::new(-y, -x, -z),
+            19 => Self::new(-y, -z, x),
+            20 => Self::new(-z, x, -y),
+            21 => Self::new(-z, y, x),
+            22 => Self::new(-z, -x, y),
+            23 => Self::new(-z, -y, -x),
+            _ => panic!("Invalid rotation {num}"),
+        }
+    }
+
+    pub fn rotations(self) -> impl Iterator<Item = Self> {
+        (0..24).map(move |num| self.rotate(num))
+    }
+}
+
+macro_rules! impl_vector_ops {
+    ($name:ident { $($field:ident),+ }) => {
+        impl<T: Add<Output = T>> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+    };
+}
+
+impl_vector_ops!(Vec2 { x, y });
+impl_vector_ops!(Vec3 { x, y, z });
+
+impl<T: fmt::Display> fmt::Display for Vec2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Vec3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+impl<T: FromStr> FromStr for Vec2<T> {
+    type Err = ParseVectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_components(s)?;
+        Ok(Self { x, y })
+    }
+}
+
+impl<T: FromStr> FromStr for Vec3<T> {
+    type Err = ParseVectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = parse_components(s)?;
+        Ok(Self { x, y, z })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Vec2::new(3, -4);
+        let b = Vec2::new(1, 2);
+
+        assert_eq!(a + b, Vec2::new(4, -2));
+        assert_eq!(a - b, Vec2::new(2, -6));
+        assert_eq!(-a, Vec2::new(-3, 4));
+        assert_eq!(a * 2, Vec2::new(6, -8));
+        assert_eq!(a.signum(), Vec2::new(1, -1));
+        assert_eq!(a.manhattan(b), 8);
+
+        let c = Vec3::new(1105, -1205, 1229);
+        let d = Vec3::new(-92, -2380, -20);
+        assert_eq!(c.manhattan(d), 3621);
+        assert_eq!(Vec2::new(2usize, 7).manhattan(Vec2::new(5, 1)), 9);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("0,9".parse::<Vec2<i64>>(), Ok(Vec2::new(0, 9)));
+        assert_eq!("-618,-824,-621".parse(), Ok(Vec3::new(-618, -824, -621)));
+        assert!("1,2,3".parse::<Vec2<i64>>().is_err());
+        assert!("1,x".parse::<Vec2<i64>>().is_err());
+
+        assert_eq!(Vec3::new(1, -2, 3).to_string(), "1,-2,3");
+    }
+
+    #[test]
+    fn test_rotations_are_distinct() {
+        let v = Vec3::new(1, 2, 3);
+        let rotations = v.rotations().collect::<HashSet<_>>();
+        assert_eq!(rotations.len(), 24);
+        assert!(rotations
+            .iter()
+            .all(|it| it.manhattan(Vec3::default()) == 6));
+    }
+}
@@ -0,0 +1,385 @@
+use std::io::Read;
+use std::process::ExitCode;
+
+use aoc_2021::cache::Cache;
+use aoc_2021::json;
+use aoc_2021::solver::{self, Answer, YEAR};
+
+const USAGE: &str =
+    "Usage: aoc2021 --day <1-25> [--part <1|2>] [--input <file|->] [--format <text|json>]
+               [--trace <file>] [--no-cache]
+       aoc2021 --clear-cache
+       aoc2021 --help
+
+Reads the puzzle input from <file>, or from stdin if --input is omitted or `-`.
+Solves both parts unless --part is given. With --trace, intermediate solver
+state is written to <file> as JSON lines (needs the `trace` feature).
+
+If AOC_CACHE_DIR is set, answers are cached there by input and crate version.
+--no-cache solves without reading or writing it, --clear-cache removes the
+cached answers from it and leaves any other files alone.";
+
+const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Help,
+    ClearCache,
+    Solve(Options),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Options {
+    day: u8,
+    parts: Vec<u8>,
+    input: String,
+    format: Format,
+    trace: Option<String>,
+    no_cache: bool,
+}
+
+// `--help` and `--clear-cache` have to be given on their own.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let args = args.into_iter().collect::<Vec<_>>();
+    let count = args.len();
+
+    let mut standalone = None;
+    let mut day = None;
+    let mut parts = None;
+    let mut input = String::from("-");
+    let mut format = Format::Text;
+    let mut trace = None;
+    let mut no_cache = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+
+        match arg.as_str() {
+            "--day" | "-d" => {
+                let value = value()?;
+                day = Some(
+                    value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|it| (1..=25).contains(it))
+                        .ok_or_else(|| format!("Invalid day: {value}"))?,
+                );
+            }
+            "--part" | "-p" => {
+                let value = value()?;
+                let part = value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|it| matches!(it, 1 | 2))
+                    .ok_or_else(|| format!("Invalid part: {value}"))?;
+                parts = Some(vec![part]);
+            }
+            "--input" | "-i" => input = value()?,
+            "--format" | "-f" => {
+                format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    other => return Err(format!("Invalid format: {other}")),
+                };
+            }
+            "--json" => format = Format::Json,
+            "--trace" => trace = Some(value()?),
+            "--no-cache" => no_cache = true,
+            "--help" | "-h" => standalone = Some(Command::Help),
+            "--clear-cache" => standalone = Some(Command::ClearCache),
+            _ => return Err(format!("Unknown argument: {arg}")),
+        }
+
+        if standalone.is_some() && count > 1 {
+            return Err(format!("{arg} takes no other arguments"));
+        }
+    }
+
+    if let Some(command) = standalone {
+        return Ok(command);
+    }
+
+    let day = day.ok_or_else(|| String::from("Missing --day"))?;
+    let registered = solver::puzzles()
+        .iter()
+        .filter(|it| it.year == YEAR && it.day == day)
+        .map(|it| it.part)
+        .collect::<Vec<_>>();
+    if registered.is_empty() {
+        return Err(format!("No puzzle for day {day}"));
+    }
+    let parts = parts.unwrap_or_else(|| registered.clone());
+    if let Some(part) = parts.iter().find(|it| !registered.contains(it)) {
+        return Err(format!("No puzzle for day {day}, part {part}"));
+    }
+
+    Ok(Command::Solve(Options {
+        day,
+        parts,
+        input,
+        format,
+        trace,
+        no_cache,
+    }))
+}
+
+fn read_input(path: &str) -> std::io::Result<String> {
+    if path == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        Ok(buf)
+    } else {
+        std::fs::read_to_string(path)
+    }
+}
+
+fn json_result(day: u8, part: u8, result: &Result<Answer, solver::Error>) -> String {
+    let value = match result {
+        Ok(Answer::Number(n)) => format!("\"answer\":{n}"),
+        Ok(Answer::Text(s)) => format!("\"answer\":{}", json::string(s)),
+        Err(err) => format!("\"error\":{}", json::string(&err.to_string())),
+    };
+
+    format!("{{\"year\":{YEAR},\"day\":{day},\"part\":{part},{value}}}")
+}
+
+fn cache() -> Option<Cache> {
+    let dir = std::env::var_os(CACHE_DIR_VAR)?;
+    Some(Cache::new(dir, env!("CARGO_PKG_VERSION")))
+}
+
+fn solve_cached(
+    cache: Option<&Cache>,
+    day: u8,
+    part: u8,
+    input: &str,
+) -> Result<Answer, solver::Error> {
+    let Some(cache) = cache else {
+        return solver::solve(YEAR, day, part, input);
+    };
+
+    if let Some(answer) = cache.get(YEAR, day, part, input) {
+        return Ok(answer);
+    }
+
+    let answer = solver::solve(YEAR, day, part, input)?;
+    if let Err(err) = cache.put(YEAR, day, part, input, &answer) {
+        eprintln!("Could not cache day {day}, part {part}: {err}");
+    }
+    Ok(answer)
+}
+
+fn clear_cache() -> ExitCode {
+    let Some(cache) = cache() else {
+        eprintln!("{CACHE_DIR_VAR} is not set, there is no cache to clear");
+        return ExitCode::FAILURE;
+    };
+
+    match cache.clear() {
+        Ok(removed) => {
+            println!("Removed {removed} cached answers");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Could not clear cache: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+fn solve_traced<R>(path: &str, f: impl FnOnce() -> R) -> std::io::Result<R> {
+    use aoc_2021::trace::{self, Collector};
+    use std::io::Write;
+    use std::rc::Rc;
+
+    let collector = Rc::new(Collector::new());
+    let results = trace::with_subscriber(collector.clone(), f);
+
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    collector.write_json_lines(&mut out)?;
+    out.flush()?;
+    Ok(results)
+}
+
+#[cfg(not(feature = "trace"))]
+fn solve_traced<R>(_: &str, _: impl FnOnce() -> R) -> std::io::Result<R> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "built without the `trace` feature",
+    ))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::ClearCache) => return clear_cache(),
+        Ok(Command::Solve(options)) => options,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let input = match read_input(&options.input) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Could not read input '{}': {err}", options.input);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // A traced run has to actually solve, so it never reads from the cache.
+    let cache = cache().filter(|_| !options.no_cache && options.trace.is_none());
+    let solve_all = || {
+        options
+            .parts
+            .iter()
+            .map(|&part| {
+                let result = solve_cached(cache.as_ref(), options.day, part, &input);
+                (part, result)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let results = match &options.trace {
+        Some(path) => match solve_traced(path, solve_all) {
+            Ok(results) => results,
+            Err(err) => {
+                eprintln!("Could not write trace '{path}': {err}");
+                return ExitCode::FAILURE;
+            }
+        },
+        None => solve_all(),
+    };
+
+    match options.format {
+        Format::Text => {
+            for (part, result) in &results {
+                match result {
+                    Ok(answer) => println!("Day {} - Part {part}: {answer}", options.day),
+                    Err(err) => eprintln!("Day {} - Part {part}: {err}", options.day),
+                }
+            }
+        }
+        Format::Json => {
+            let results = results
+                .iter()
+                .map(|(part, result)| json_result(options.day, *part, result))
+                .collect::<Vec<_>>();
+            println!("[{}]", results.join(","));
+        }
+    }
+
+    if results.iter().all(|(_, result)| result.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(ToString::to_string).collect()
+    }
+
+    fn solve_args(s: &str) -> Result<Options, String> {
+        match parse_args(args(s))? {
+            Command::Solve(options) => Ok(options),
+            other => Err(format!("{other:?}")),
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args("--day 7 --part 2 --input day7.txt --format json"));
+        assert_eq!(
+            options,
+            Ok(Command::Solve(Options {
+                day: 7,
+                parts: vec![2],
+                input: String::from("day7.txt"),
+                format: Format::Json,
+                trace: None,
+                no_cache: false,
+            }))
+        );
+
+        let options = solve_args("--day 14 --trace trace.jsonl");
+        assert_eq!(
+            options.map(|it| it.trace),
+            Ok(Some(String::from("trace.jsonl")))
+        );
+
+        let options = solve_args("--day 22 --no-cache");
+        assert_eq!(options.map(|it| it.no_cache), Ok(true));
+
+        let options = solve_args("--day 25");
+        assert_eq!(options.map(|it| it.parts), Ok(vec![1]));
+
+        assert!(parse_args(args("--part 1")).is_err());
+        assert!(parse_args(args("--day 0 --part 1")).is_err());
+        assert_eq!(
+            parse_args(args("--day 26")),
+            Err(String::from("Invalid day: 26"))
+        );
+        assert!(parse_args(args("--day 1 --part 3")).is_err());
+        assert_eq!(
+            parse_args(args("--day 25 --part 2")),
+            Err(String::from("No puzzle for day 25, part 2"))
+        );
+        assert!(parse_args(args("--day 1 --input")).is_err());
+    }
+
+    #[test]
+    fn test_standalone_flags() {
+        assert_eq!(parse_args(args("--help")), Ok(Command::Help));
+        assert_eq!(parse_args(args("-h")), Ok(Command::Help));
+        assert_eq!(parse_args(args("--clear-cache")), Ok(Command::ClearCache));
+
+        assert_eq!(
+            parse_args(args("--clear-cache --day 1")),
+            Err(String::from("--clear-cache takes no other arguments"))
+        );
+        assert_eq!(
+            parse_args(args("--day 1 --help")),
+            Err(String::from("--help takes no other arguments"))
+        );
+        assert_eq!(
+            solve_args("--day 1 --input --help").map(|it| it.input),
+            Ok(String::from("--help"))
+        );
+        assert_eq!(
+            solve_args("--day 1 --trace --clear-cache").map(|it| it.trace),
+            Ok(Some(String::from("--clear-cache")))
+        );
+    }
+
+    #[test]
+    fn test_json_result() {
+        let res = json_result(13, 2, &Ok(Answer::Text(String::from("\n# \"#\"\n"))));
+        assert_eq!(
+            res,
+            r##"{"year":2021,"day":13,"part":2,"answer":"\n# \"#\"\n"}"##
+        );
+
+        let res = json_result(1, 1, &Ok(Answer::Number(7)));
+        assert_eq!(res, r#"{"year":2021,"day":1,"part":1,"answer":7}"#);
+    }
+}
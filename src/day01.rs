@@ -1,24 +1,157 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use aoc_utils::parse::{parse_line, parse_lines, ParseError};
+use aoc_utils::stream::{self, StreamError};
+use aoc_utils::trace;
+
+const EXPECTED: &str = "a depth measurement";
 
 #[aoc_generator(day1)]
-pub fn generate(inp: &str) -> Vec<usize> {
-    inp.lines().filter_map(|it| it.parse().ok()).collect()
+pub fn generate(inp: &str) -> Result<Vec<usize>, ParseError> {
+    parse_lines(1, inp, EXPECTED)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WindowStats {
+    pub increases: usize,
+    pub decreases: usize,
+    pub plateaus: usize,
+    // Most consecutive increases between neighbouring windows.
+    pub longest_increasing_run: usize,
+}
+
+// Takes readings one at a time, keeping only the last `window` of them, so it
+// can follow an unbounded sweep. Neighbouring window sums differ only by the
+// reading entering and the one leaving, so those are all that is compared.
+#[derive(Clone, Debug)]
+pub struct SonarAnalyzer {
+    window: usize,
+    last: VecDeque<usize>,
+    // A window holds at most `usize::MAX` readings, so its sum always fits.
+    sum: u128,
+    run: usize,
+    readings: usize,
+    stats: WindowStats,
+}
+
+impl SonarAnalyzer {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            last: VecDeque::new(),
+            sum: 0,
+            run: 0,
+            readings: 0,
+            stats: WindowStats::default(),
+        }
+    }
+
+    // Nothing is compared for an empty window.
+    pub fn push(&mut self, depth: usize) {
+        self.readings += 1;
+        if self.window == 0 {
+            return;
+        }
+
+        self.last.push_back(depth);
+        self.sum += depth as u128;
+        if self.last.len() <= self.window {
+            return;
+        }
+
+        let old = self.last.pop_front().expect("Window is full");
+        self.sum -= old as u128;
+
+        match depth.cmp(&old) {
+            Ordering::Greater => {
+                self.stats.increases += 1;
+                self.run += 1;
+                self.stats.longest_increasing_run = self.stats.longest_increasing_run.max(self.run);
+            }
+            Ordering::Less => {
+                self.stats.decreases += 1;
+                self.run = 0;
+            }
+            Ordering::Equal => {
+                self.stats.plateaus += 1;
+                self.run = 0;
+            }
+        }
+    }
+
+    pub const fn stats(&self) -> WindowStats {
+        self.stats
+    }
+
+    pub const fn readings(&self) -> usize {
+        self.readings
+    }
+
+    // Sum of the latest `window` readings, once that many have been taken and
+    // as long as it fits in a `usize`.
+    pub fn window_sum(&self) -> Option<usize> {
+        usize::try_from(self.sum)
+            .ok()
+            .filter(|_| self.window > 0 && self.last.len() == self.window)
+    }
+}
+
+// Compares each sum of `window` consecutive measurements with the previous one.
+pub fn count_increases(inp: &[usize], window: usize) -> WindowStats {
+    if inp.len() <= window {
+        return WindowStats::default();
+    }
+
+    let mut analyzer = SonarAnalyzer::new(window);
+    for &depth in inp {
+        analyzer.push(depth);
+    }
+
+    let stats = analyzer.stats();
+    trace::event!(
+        1,
+        "increases",
+        window = window,
+        count = stats.increases,
+        decreases = stats.decreases,
+        plateaus = stats.plateaus,
+        longest_increasing_run = stats.longest_increasing_run,
+    );
+    stats
+}
+
+// Comparing sliding sums of `window` measurements only depends on the values
+// `window` apart, so only the last `window` measurements are kept.
+fn count_increases_streaming<R: BufRead>(reader: R, window: usize) -> Result<usize, StreamError> {
+    let mut analyzer = SonarAnalyzer::new(window);
+    for depth in stream::parse_lines(reader, |idx, line| {
+        parse_line::<usize>(1, idx, line, EXPECTED)
+    }) {
+        analyzer.push(depth?);
+    }
+
+    Ok(analyzer.stats().increases)
+}
+
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<usize, StreamError> {
+    count_increases_streaming(reader, 1)
+}
+
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<usize, StreamError> {
+    count_increases_streaming(reader, 3)
 }
 
 #[aoc(day1, part1)]
 pub fn part1(inp: &[usize]) -> usize {
-    inp.iter().tuple_windows().filter(|(a, b)| b > a).count()
+    count_increases(inp, 1).increases
 }
 
 #[aoc(day1, part2)]
 pub fn part2(inp: &[usize]) -> usize {
-    inp.iter()
-        .tuple_windows()
-        .map(|(a, b, c)| a + b + c)
-        .tuple_windows()
-        .filter(|(a, b)| b > a)
-        .count()
+    count_increases(inp, 3).increases
 }
 
 #[cfg(test)]
@@ -38,4 +171,104 @@ mod tests {
         let res = part2(&inp);
         assert_eq!(res, 5);
     }
+
+    #[test]
+    fn test_window_stats() {
+        let inp = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert_eq!(
+            count_increases(&inp, 1),
+            WindowStats {
+                increases: 7,
+                decreases: 2,
+                plateaus: 0,
+                longest_increasing_run: 3,
+            }
+        );
+        assert_eq!(
+            count_increases(&inp, 3),
+            WindowStats {
+                increases: 5,
+                decreases: 1,
+                plateaus: 1,
+                longest_increasing_run: 4,
+            }
+        );
+        assert_eq!(count_increases(&inp, 9).increases, 1);
+        assert_eq!(count_increases(&inp, 10), WindowStats::default());
+        assert_eq!(count_increases(&inp, 0), WindowStats::default());
+    }
+
+    #[test]
+    fn test_huge_window() {
+        assert_eq!(count_increases(&[1, 2, 3], 1 << 40), WindowStats::default());
+        assert_eq!(
+            count_increases(&[1, 2, 3], usize::MAX),
+            WindowStats::default()
+        );
+
+        let mut analyzer = SonarAnalyzer::new(usize::MAX);
+        for depth in [1, 2, 3] {
+            analyzer.push(depth);
+        }
+        assert_eq!(analyzer.readings(), 3);
+        assert_eq!(analyzer.window_sum(), None);
+        assert_eq!(analyzer.stats(), WindowStats::default());
+    }
+
+    #[test]
+    fn test_analyzer_reports_while_reading() {
+        let mut analyzer = SonarAnalyzer::new(3);
+        for depth in [199, 200, 208, 210] {
+            analyzer.push(depth);
+        }
+        assert_eq!(analyzer.readings(), 4);
+        assert_eq!(analyzer.window_sum(), Some(618));
+        assert_eq!(analyzer.stats().increases, 1);
+
+        for depth in [200, 207, 240, 269, 260, 263] {
+            analyzer.push(depth);
+        }
+        assert_eq!(analyzer.window_sum(), Some(792));
+        assert_eq!(analyzer.stats().increases, 5);
+        assert_eq!(analyzer.stats().plateaus, 1);
+
+        let mut analyzer = SonarAnalyzer::new(2);
+        analyzer.push(1);
+        assert_eq!(analyzer.window_sum(), None);
+    }
+
+    #[test]
+    fn test_large_readings_do_not_overflow() {
+        let stats = count_increases(&[usize::MAX, usize::MAX, 0, 1, usize::MAX], 2);
+        assert_eq!((stats.increases, stats.decreases), (1, 2));
+
+        let mut analyzer = SonarAnalyzer::new(2);
+        for depth in [usize::MAX, usize::MAX] {
+            analyzer.push(depth);
+        }
+        assert_eq!(analyzer.window_sum(), None);
+
+        analyzer.push(0);
+        assert_eq!(analyzer.window_sum(), Some(usize::MAX));
+    }
+
+    #[test]
+    fn test_streaming() {
+        let inp = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        assert_eq!(part1_streaming(inp.as_bytes()).ok(), Some(7));
+        assert_eq!(part2_streaming(inp.as_bytes()).ok(), Some(5));
+
+        let inp = "199\n200\n208\n\n";
+        assert_eq!(part1_streaming(inp.as_bytes()).ok(), Some(2));
+        assert_eq!(
+            crate::solve(2021, 1, 1, inp).ok(),
+            Some(crate::Answer::Number(2))
+        );
+    }
+
+    #[test]
+    fn test_invalid_measurement() {
+        let res = generate("199\n200\n2O8\n210");
+        assert_eq!(res, Err(ParseError::new(1, 3, 1, "a depth measurement")));
+    }
 }
@@ -1,7 +1,16 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use parse_display::{Display, FromStr};
+use std::io::{self, BufRead, Write};
+use std::{error, fmt, iter, mem};
 
-#[derive(Display, FromStr, Debug, Copy, Clone)]
+use aoc_utils::parse::{column_of, parse_line, parse_lines, ParseError};
+use aoc_utils::solver::Unanswered;
+use aoc_utils::stream::{self, StreamError};
+use aoc_utils::trace;
+
+const EXPECTED: &str = "a command like `forward 5`";
+
+#[derive(Display, FromStr, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
     #[display("forward {0}")]
     Forward(i64),
@@ -13,57 +22,402 @@ pub enum Direction {
     Up(i64),
 }
 
-#[derive(Default)]
-struct State {
-    horizontal_position: i64,
-    depth: i64,
-    aim: i64,
+// A single line of a program for `parse_program`, which knows a few more
+// commands than the puzzle input uses.
+#[derive(Display, FromStr, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    #[display("forward {0}")]
+    Forward(i64),
+
+    #[display("down {0}")]
+    Down(i64),
+
+    #[display("up {0}")]
+    Up(i64),
+
+    #[display("back {0}")]
+    Back(i64),
+
+    #[display("reset")]
+    Reset,
+}
+
+impl From<Direction> for Instruction {
+    fn from(dir: Direction) -> Self {
+        match dir {
+            Direction::Forward(x) => Self::Forward(x),
+            Direction::Down(x) => Self::Down(x),
+            Direction::Up(x) => Self::Up(x),
+        }
+    }
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    pub horizontal_position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+impl State {
+    pub const fn product(&self) -> Option<i64> {
+        self.horizontal_position.checked_mul(self.depth)
+    }
+}
+
+const POSITION_OVERFLOW: &str = "submarine position overflowed i64";
+
+// Positions are plain `i64`s, so unlike the puzzles counting with `Count` the
+// `bigint` feature does not help here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositionOverflow;
+
+impl fmt::Display for PositionOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(POSITION_OVERFLOW)
+    }
+}
+
+impl error::Error for PositionOverflow {}
+
+impl From<PositionOverflow> for Unanswered {
+    fn from(PositionOverflow: PositionOverflow) -> Self {
+        Self::Failed(POSITION_OVERFLOW)
+    }
+}
+
+impl From<PositionOverflow> for StreamError {
+    fn from(err: PositionOverflow) -> Self {
+        Unanswered::from(err).into()
+    }
+}
+
+// How each command moves the submarine, or `None` if the new state does not fit
+// in an `i64`. Only `forward` and `down` have to be given, the other commands
+// are defined in terms of them.
+pub trait Model {
+    fn forward(&self, state: State, x: i64) -> Option<State>;
+
+    fn down(&self, state: State, x: i64) -> Option<State>;
+
+    fn up(&self, state: State, x: i64) -> Option<State> {
+        self.down(state, x.checked_neg()?)
+    }
+
+    fn back(&self, state: State, x: i64) -> Option<State> {
+        self.forward(state, x.checked_neg()?)
+    }
+
+    fn reset(&self, _: State) -> Option<State> {
+        Some(State::default())
+    }
+
+    fn step(&self, state: State, instruction: Instruction) -> Result<State, PositionOverflow> {
+        match instruction {
+            Instruction::Forward(x) => self.forward(state, x),
+            Instruction::Down(x) => self.down(state, x),
+            Instruction::Up(x) => self.up(state, x),
+            Instruction::Back(x) => self.back(state, x),
+            Instruction::Reset => self.reset(state),
+        }
+        .ok_or(PositionOverflow)
+    }
+}
+
+// `down` and `up` change the depth directly.
+#[derive(Debug, Copy, Clone)]
+pub struct Direct;
+
+impl Model for Direct {
+    fn forward(&self, state: State, x: i64) -> Option<State> {
+        Some(State {
+            horizontal_position: state.horizontal_position.checked_add(x)?,
+            ..state
+        })
+    }
+
+    fn down(&self, state: State, x: i64) -> Option<State> {
+        Some(State {
+            depth: state.depth.checked_add(x)?,
+            ..state
+        })
+    }
+}
+
+// `down` and `up` change the aim, which `forward` turns into depth.
+#[derive(Debug, Copy, Clone)]
+pub struct Aim;
+
+impl Model for Aim {
+    fn forward(&self, state: State, x: i64) -> Option<State> {
+        Some(State {
+            horizontal_position: state.horizontal_position.checked_add(x)?,
+            depth: state.depth.checked_add(state.aim.checked_mul(x)?)?,
+            ..state
+        })
+    }
+
+    fn down(&self, state: State, x: i64) -> Option<State> {
+        Some(State {
+            aim: state.aim.checked_add(x)?,
+            ..state
+        })
+    }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Step(Instruction),
+    Repeat { times: usize, body: Vec<Self> },
+}
+
+// Keeps the course of any program small enough to hold in memory.
+pub const MAX_STEPS: usize = 1_000_000;
+
 #[aoc_generator(day2)]
-pub fn generate(inp: &str) -> Vec<Direction> {
-    inp.lines().filter_map(|it| it.parse().ok()).collect()
+pub fn generate(inp: &str) -> Result<Vec<Direction>, ParseError> {
+    parse_lines(2, inp, EXPECTED)
 }
 
-#[aoc(day2, part1)]
-pub fn part1(inp: &[Direction]) -> i64 {
-    let res = inp.iter().fold(State::default(), |acc, &it| match it {
-        Direction::Forward(x) => State {
-            horizontal_position: acc.horizontal_position + x,
-            ..acc
-        },
-        Direction::Down(x) => State {
-            depth: acc.depth + x,
-            ..acc
-        },
-        Direction::Up(x) => State {
-            depth: acc.depth - x,
-            ..acc
-        },
+// Like `generate`, but also accepts `back <n>`, `reset` and `repeat <n>` ... `end`
+// blocks, which may nest. Programs may take at most `MAX_STEPS` steps once their
+// repeat blocks are unrolled.
+pub fn parse_program(inp: &str) -> Result<Vec<Command>, ParseError> {
+    let too_long = |line_no, column| {
+        ParseError::new(
+            2,
+            line_no,
+            column,
+            format!("a program of at most {MAX_STEPS} steps"),
+        )
+    };
+
+    let mut open_blocks = Vec::new();
+    let mut program = Vec::new();
+    // Unrolled steps in `program`, never more than `MAX_STEPS`.
+    let mut steps = 0_usize;
+
+    for (idx, line) in inp.lines().enumerate() {
+        let (command, len) = if let Some(count) = line.strip_prefix("repeat ") {
+            let column = column_of(line, count);
+            let times = count
+                .parse::<usize>()
+                .map_err(|_| ParseError::new(2, idx + 1, column, "a repeat count"))?;
+            open_blocks.push((
+                idx + 1,
+                column,
+                times,
+                mem::take(&mut program),
+                mem::take(&mut steps),
+            ));
+            continue;
+        } else if line == "end" {
+            let Some((line_no, column, times, outer, outer_steps)) = open_blocks.pop() else {
+                return Err(ParseError::new(2, idx + 1, 1, "a `repeat` before `end`"));
+            };
+            let len = steps
+                .checked_mul(times)
+                .filter(|&it| it <= MAX_STEPS)
+                .ok_or_else(|| too_long(line_no, column))?;
+            steps = outer_steps;
+
+            // Blocks without any steps are dropped, so that unrolling them does not
+            // spin through their repeats.
+            let body = mem::replace(&mut program, outer);
+            if len == 0 {
+                continue;
+            }
+            (Command::Repeat { times, body }, len)
+        } else {
+            (Command::Step(parse_line(2, idx, line, EXPECTED)?), 1)
+        };
+
+        steps += len;
+        if steps > MAX_STEPS {
+            return Err(too_long(idx + 1, 1));
+        }
+        program.push(command);
+    }
+
+    if let Some((line_no, ..)) = open_blocks.pop() {
+        return Err(ParseError::new(
+            2,
+            inp.lines().count() + 1,
+            1,
+            format!("an `end` for the `repeat` on line {line_no}"),
+        ));
+    }
+
+    Ok(program)
+}
+
+fn step<M: Model>(
+    model: &M,
+    state: State,
+    instruction: Instruction,
+) -> Result<State, PositionOverflow> {
+    let next = model.step(state, instruction)?;
+    trace::event!(
+        2,
+        "step",
+        command = instruction.to_string(),
+        horizontal_position = next.horizontal_position,
+        depth = next.depth,
+        aim = next.aim,
+    );
+
+    Ok(next)
+}
+
+// The program with its repeat blocks unrolled.
+fn instructions(program: &[Command]) -> Box<dyn Iterator<Item = Instruction> + '_> {
+    Box::new(program.iter().flat_map(|command| match command {
+        Command::Step(instruction) => Box::new(iter::once(*instruction)),
+        Command::Repeat { times, body } => {
+            Box::new((0..*times).flat_map(|_| instructions(body))) as Box<dyn Iterator<Item = _>>
+        }
+    }))
+}
+
+// Every state the submarine passes through, starting with the initial one. It
+// ends with an error if a state does not fit in an `i64`. The states can be
+// streamed to `write_csv`, but `write_svg` holds the whole course since its
+// `viewBox` depends on every state.
+pub fn course<'a, M: Model>(
+    model: &'a M,
+    program: &'a [Command],
+) -> impl Iterator<Item = Result<State, PositionOverflow>> + 'a {
+    let mut state = Some(State::default());
+    let moves = instructions(program).map_while(move |instruction| {
+        let next = step(model, state?, instruction);
+        state = next.ok();
+        Some(next)
     });
 
-    res.horizontal_position * res.depth
+    iter::once(Ok(State::default())).chain(moves)
+}
+
+pub fn interpret<M: Model>(model: &M, program: &[Command]) -> Result<Vec<State>, PositionOverflow> {
+    course(model, program).collect()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min_position: i64,
+    pub max_position: i64,
+    pub min_depth: i64,
+    pub max_depth: i64,
+    // Where in the course `max_depth` was first reached.
+    pub deepest_step: usize,
+    pub deepest: State,
+}
+
+impl BoundingBox {
+    pub fn of(course: impl IntoIterator<Item = State>) -> Option<Self> {
+        let mut course = course.into_iter();
+        let first = course.next()?;
+        let mut bounds = Self {
+            min_position: first.horizontal_position,
+            max_position: first.horizontal_position,
+            min_depth: first.depth,
+            max_depth: first.depth,
+            deepest_step: 0,
+            deepest: first,
+        };
+
+        for (step, state) in course.enumerate() {
+            bounds.min_position = bounds.min_position.min(state.horizontal_position);
+            bounds.max_position = bounds.max_position.max(state.horizontal_position);
+            bounds.min_depth = bounds.min_depth.min(state.depth);
+            if state.depth > bounds.max_depth {
+                bounds.max_depth = state.depth;
+                bounds.deepest_step = step + 1;
+                bounds.deepest = state;
+            }
+        }
+
+        Some(bounds)
+    }
+}
+
+pub fn write_csv(course: impl IntoIterator<Item = State>, mut out: impl Write) -> io::Result<()> {
+    writeln!(out, "step,horizontal_position,depth,aim")?;
+    for (step, state) in course.into_iter().enumerate() {
+        writeln!(
+            out,
+            "{step},{},{},{}",
+            state.horizontal_position, state.depth, state.aim
+        )?;
+    }
+
+    Ok(())
+}
+
+// Depth grows downwards, as does the y axis in SVG, so states map onto points
+// directly.
+pub fn write_svg(course: impl IntoIterator<Item = State>, mut out: impl Write) -> io::Result<()> {
+    let course = course.into_iter().collect::<Vec<_>>();
+    let Some(bounds) = BoundingBox::of(course.iter().copied()) else {
+        return writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg"/>"#);
+    };
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        bounds.min_position,
+        bounds.min_depth,
+        bounds
+            .max_position
+            .saturating_sub(bounds.min_position)
+            .max(1),
+        bounds.max_depth.saturating_sub(bounds.min_depth).max(1),
+    )?;
+
+    write!(out, r#"  <polyline points=""#)?;
+    for (idx, state) in course.iter().enumerate() {
+        let sep = if idx == 0 { "" } else { " " };
+        write!(out, "{sep}{},{}", state.horizontal_position, state.depth)?;
+    }
+    writeln!(
+        out,
+        r#"" fill="none" stroke="black" vector-effect="non-scaling-stroke"/>"#
+    )?;
+    writeln!(out, "</svg>")
+}
+
+fn fold_streaming<R: BufRead, M: Model>(reader: R, model: &M) -> Result<i64, StreamError> {
+    let res = stream::parse_lines(reader, |idx, line| parse_line(2, idx, line, EXPECTED))
+        .try_fold(State::default(), |acc, dir: Result<Direction, _>| {
+            Ok::<_, StreamError>(step(model, acc, dir?.into())?)
+        })?;
+
+    Ok(res.product().ok_or(PositionOverflow)?)
+}
+
+fn run<M: Model>(inp: &[Direction], model: &M) -> Result<i64, PositionOverflow> {
+    let res = inp
+        .iter()
+        .try_fold(State::default(), |acc, &dir| step(model, acc, dir.into()))?;
+
+    res.product().ok_or(PositionOverflow)
+}
+
+#[aoc(day2, part1)]
+pub fn part1(inp: &[Direction]) -> Result<i64, PositionOverflow> {
+    run(inp, &Direct)
 }
 
 #[aoc(day2, part2)]
-pub fn part2(inp: &[Direction]) -> i64 {
-    let res = inp.iter().fold(State::default(), |acc, &it| match it {
-        Direction::Forward(x) => State {
-            horizontal_position: acc.horizontal_position + x,
-            depth: acc.depth + acc.aim * x,
-            ..acc
-        },
-        Direction::Down(x) => State {
-            aim: acc.aim + x,
-            ..acc
-        },
-        Direction::Up(x) => State {
-            aim: acc.aim - x,
-            ..acc
-        },
-    });
+pub fn part2(inp: &[Direction]) -> Result<i64, PositionOverflow> {
+    run(inp, &Aim)
+}
+
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<i64, StreamError> {
+    fold_streaming(reader, &Direct)
+}
 
-    res.horizontal_position * res.depth
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<i64, StreamError> {
+    fold_streaming(reader, &Aim)
 }
 
 #[cfg(test)]
@@ -79,17 +433,233 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
 
-        assert_eq!(res, 150);
+        assert_eq!(res, Ok(150));
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
 
-        assert_eq!(res, 900);
+        assert_eq!(res, Ok(900));
+    }
+
+    #[test]
+    fn test_interpreter() {
+        let program = "forward 5\n\
+                       repeat 2\n\
+                       down 2\n\
+                       repeat 3\n\
+                       forward 1\n\
+                       end\n\
+                       end\n\
+                       back 2\n\
+                       up 1";
+        let Ok(program) = parse_program(program) else {
+            panic!("Could not parse test program")
+        };
+
+        let Ok(trajectory) = interpret(&Direct, &program) else {
+            panic!("Test program does not overflow")
+        };
+        assert_eq!(trajectory.len(), 12);
+        assert_eq!(trajectory[0], State::default());
+        assert_eq!(
+            trajectory.last(),
+            Some(&State {
+                horizontal_position: 9,
+                depth: 3,
+                aim: 0,
+            })
+        );
+
+        let trajectory = interpret(&Aim, &program).ok();
+        assert_eq!(
+            trajectory.as_ref().and_then(|it| it.last()),
+            Some(&State {
+                horizontal_position: 9,
+                depth: 10,
+                aim: 3,
+            })
+        );
+
+        let Ok(program) = parse_program("down 4\nforward 1\nreset\nforward 2") else {
+            panic!("Could not parse test program")
+        };
+        assert_eq!(
+            interpret(&Aim, &program).map(|it| it[3]),
+            Ok(State::default())
+        );
+        assert!(generate("forward 1\nback 1").is_err());
+        assert!(generate("reset").is_err());
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let Ok(program) = parse_program("down 1\nforward 9223372036854775807\nforward 2") else {
+            panic!("Could not parse test program")
+        };
+        assert_eq!(interpret(&Direct, &program), Err(PositionOverflow));
+        assert_eq!(
+            course(&Aim, &program).collect::<Vec<_>>(),
+            [
+                Ok(State::default()),
+                Ok(State {
+                    aim: 1,
+                    ..State::default()
+                }),
+                Ok(State {
+                    horizontal_position: i64::MAX,
+                    depth: i64::MAX,
+                    aim: 1,
+                }),
+                Err(PositionOverflow),
+            ]
+        );
+
+        let Ok(gen) = generate("forward 4294967296\ndown 4294967296") else {
+            panic!("Could not parse test input")
+        };
+        assert_eq!(part1(&gen), Err(PositionOverflow));
+        assert!(matches!(
+            part1_streaming(&b"forward 4294967296\ndown 4294967296"[..]),
+            Err(StreamError::Unanswered(Unanswered::Failed(
+                POSITION_OVERFLOW
+            )))
+        ));
+        assert_eq!(
+            crate::solve(2021, 2, 1, "forward 4294967296\ndown 4294967296")
+                .map_err(|it| it.to_string()),
+            Err(String::from(
+                "day 2, part 1: submarine position overflowed i64"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_course_exports() {
+        let Ok(program) = parse_program("down 5\nforward 2\nup 3\nforward 1") else {
+            panic!("Could not parse test program")
+        };
+        let (Ok(direct), Ok(aim)) = (interpret(&Direct, &program), interpret(&Aim, &program))
+        else {
+            panic!("Test program does not overflow")
+        };
+
+        assert_eq!(
+            BoundingBox::of(direct.iter().copied()),
+            Some(BoundingBox {
+                min_position: 0,
+                max_position: 3,
+                min_depth: 0,
+                max_depth: 5,
+                deepest_step: 1,
+                deepest: State {
+                    horizontal_position: 0,
+                    depth: 5,
+                    aim: 0,
+                },
+            })
+        );
+        assert_eq!(BoundingBox::of(iter::empty()), None);
+
+        let mut csv = Vec::new();
+        assert!(write_csv(aim, &mut csv).is_ok());
+        assert_eq!(
+            String::from_utf8_lossy(&csv),
+            "step,horizontal_position,depth,aim\n\
+             0,0,0,0\n\
+             1,0,0,5\n\
+             2,2,10,5\n\
+             3,2,10,2\n\
+             4,3,12,2\n"
+        );
+
+        let mut svg = Vec::new();
+        assert!(write_svg(direct, &mut svg).is_ok());
+        let svg = String::from_utf8_lossy(&svg);
+        assert!(svg.contains(r#"viewBox="0 0 3 5""#), "{svg}");
+        assert!(svg.contains(r#"points="0,0 0,5 2,5 2,2 3,2""#), "{svg}");
+
+        let far = State {
+            horizontal_position: i64::MAX,
+            depth: i64::MIN,
+            aim: 0,
+        };
+        assert!(write_svg([State::default(), far], io::sink()).is_ok());
+    }
+
+    #[test]
+    fn test_program_errors() {
+        assert_eq!(
+            parse_program("forward 1\nrepeat x\nend"),
+            Err(ParseError::new(2, 2, 8, "a repeat count"))
+        );
+        assert_eq!(
+            parse_program("forward 1\nend"),
+            Err(ParseError::new(2, 2, 1, "a `repeat` before `end`"))
+        );
+        assert_eq!(
+            parse_program("repeat 2\nforward 1\nsideways 2"),
+            Err(ParseError::new(2, 3, 1, EXPECTED))
+        );
+        assert_eq!(
+            parse_program("repeat 2\nforward 1"),
+            Err(ParseError::new(
+                2,
+                3,
+                1,
+                "an `end` for the `repeat` on line 1"
+            ))
+        );
+
+        let too_long = format!("a program of at most {MAX_STEPS} steps");
+        assert_eq!(
+            parse_program("up 1\nrepeat 18446744073709551615\nforward 1\nend"),
+            Err(ParseError::new(2, 2, 8, &too_long))
+        );
+        assert_eq!(
+            parse_program("repeat 1000\nrepeat 1000\nforward 1\nend\nend\nup 1"),
+            Err(ParseError::new(2, 6, 1, &too_long))
+        );
+        assert!(parse_program("repeat 1000\nrepeat 1000\nforward 1\nend\nend").is_ok());
+        assert_eq!(
+            parse_program("repeat 18446744073709551615\nrepeat 2\nend\nend\nup 1"),
+            Ok(vec![Command::Step(Instruction::Up(1))])
+        );
+    }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(part1_streaming(TEST_DATA.as_bytes()).ok(), Some(150));
+        assert_eq!(part2_streaming(TEST_DATA.as_bytes()).ok(), Some(900));
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace_streaming_steps() {
+        use aoc_utils::json::Value;
+        use aoc_utils::trace::{self, Collector};
+        use std::rc::Rc;
+
+        let collector = Rc::new(Collector::new());
+        let _ = trace::with_subscriber(collector.clone(), || part2_streaming(TEST_DATA.as_bytes()));
+
+        let steps = collector
+            .events()
+            .iter()
+            .map(|it| it.field("command").cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[0], Some(Value::from("forward 5")));
     }
 }
@@ -1,89 +1,280 @@
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::io::BufRead;
+use std::ops::Range;
 
-#[aoc_generator(day3)]
-pub fn generate(inp: &str) -> Vec<String> {
-    inp.lines().map(ToString::to_string).collect()
+use aoc_utils::count::{Count, Overflow};
+use aoc_utils::parse::{first_line, ParseError};
+use aoc_utils::solver::Unanswered;
+use aoc_utils::stream::{self, StreamError};
+use aoc_utils::trace;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+fn check_line(idx: usize, line: &str, width: usize) -> Result<(), ParseError> {
+    if let Some(pos) = line.chars().position(|c| c != '0' && c != '1') {
+        return Err(ParseError::new(3, idx + 1, pos + 1, "a binary digit"));
+    }
+
+    if line.len() != width {
+        return Err(ParseError::new(
+            3,
+            idx + 1,
+            line.len().min(width) + 1,
+            format!("a binary number with {width} digits"),
+        ));
+    }
+
+    Ok(())
 }
 
-fn count_zero_bits_at_idx(inp: &[String], idx: usize) -> usize {
-    inp.iter()
-        .filter(|it| it.chars().nth(idx).expect("idx is in range") == '0')
-        .count()
+// All numbers are packed into one buffer, `words_per_row` words each and most
+// significant bit first, so sorting the rows orders them like the binary strings
+// they were read from. Every column is also kept as a bitset over the rows to
+// count its ones with popcounts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    width: usize,
+    len: usize,
+    words_per_row: usize,
+    rows: Vec<u64>,
+    columns: Vec<u64>,
 }
 
-fn select_bit_at_idx<P>(inp: &[String], idx: usize, pred: P) -> char
-where
-    P: Fn(usize, usize) -> char,
-{
-    let zero_count = count_zero_bits_at_idx(inp, idx);
-    let one_count = inp.len() - zero_count;
+fn bit(words: &[u64], idx: usize) -> bool {
+    words[idx / WORD_BITS] >> (WORD_BITS - 1 - idx % WORD_BITS) & 1 == 1
+}
 
-    pred(zero_count, one_count)
+fn set_bit(words: &mut [u64], idx: usize) {
+    words[idx / WORD_BITS] |= 1 << (WORD_BITS - 1 - idx % WORD_BITS);
 }
 
-fn most_common_at_idx(inp: &[String], idx: usize) -> char {
-    select_bit_at_idx(inp, idx, |z, o| if z > o { '0' } else { '1' })
+// The number with `width` bits, the `idx`th of them given by `bits`.
+fn to_count(width: usize, bits: impl Fn(usize) -> bool) -> Result<Count, Overflow> {
+    (0..width).try_fold(Count::zero(), |acc, idx| {
+        let acc = acc.checked_add(&acc)?;
+        acc.checked_add(&Count::from(u64::from(bits(idx))))
+    })
 }
 
-fn least_common_at_idx(inp: &[String], idx: usize) -> char {
-    select_bit_at_idx(inp, idx, |z, o| if z <= o { '0' } else { '1' })
+impl Report {
+    const fn new(width: usize) -> Self {
+        Self {
+            width,
+            len: 0,
+            words_per_row: width.div_ceil(WORD_BITS),
+            rows: Vec::new(),
+            columns: Vec::new(),
+        }
+    }
+
+    // Takes a line already checked to have `width` binary digits.
+    fn push(&mut self, line: &str) {
+        let start = self.rows.len();
+        self.rows.resize(start + self.words_per_row, 0);
+        for (pos, _) in line.bytes().enumerate().filter(|(_, b)| *b == b'1') {
+            set_bit(&mut self.rows[start..], pos);
+        }
+        self.len += 1;
+    }
+
+    // Sorts the rows and fills in the columns once every line has been pushed.
+    fn finish(mut self) -> Self {
+        let mut order = (0..self.len).collect::<Vec<_>>();
+        order.sort_unstable_by(|&a, &b| self.row(a).cmp(self.row(b)));
+        self.rows = order.iter().flat_map(|&it| self.row(it)).copied().collect();
+
+        let words_per_column = self.len.div_ceil(WORD_BITS);
+        let mut columns = vec![0; self.width * words_per_column];
+        for row in 0..self.len {
+            for idx in (0..self.width).filter(|&idx| bit(self.row(row), idx)) {
+                let start = idx * words_per_column;
+                set_bit(&mut columns[start..start + words_per_column], row);
+            }
+        }
+        self.columns = columns;
+
+        self
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        &self.rows[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    // The first of `rows` with a one at `idx`. Sorted rows that share all bits
+    // before `idx` have their zeros there first.
+    fn first_one(&self, rows: Range<usize>, idx: usize) -> usize {
+        let (mut lo, mut hi) = (rows.start, rows.end);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if bit(self.row(mid), idx) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        lo
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn ones_at(&self, idx: usize) -> usize {
+        let words_per_column = self.len.div_ceil(WORD_BITS);
+        self.columns[idx * words_per_column..(idx + 1) * words_per_column]
+            .iter()
+            .map(|it| it.count_ones() as usize)
+            .sum()
+    }
 }
 
-fn from_binary(number: &str) -> i64 {
-    i64::from_str_radix(number, 2).expect("number is definitely binary")
+#[aoc_generator(day3)]
+pub fn generate(inp: &str) -> Result<Report, ParseError> {
+    let width = first_line(3, inp, "a binary number")?.len();
+
+    let mut report = Report::new(width);
+    for (idx, line) in inp.lines().enumerate() {
+        check_line(idx, line, width)?;
+        report.push(line);
+    }
+
+    Ok(report.finish())
 }
 
-fn calculate_rate<P>(inp: &[String], pred: P) -> i64
+// Calls `f` with every line of the report and returns the width of its numbers.
+fn for_each_line<R, F>(reader: R, mut f: F) -> Result<usize, StreamError>
 where
-    P: Fn(&[String], usize) -> char,
+    R: BufRead,
+    F: FnMut(usize, &str),
 {
-    let length = inp[0].len();
-    let mut result = String::with_capacity(length);
+    let mut width = None;
+
+    for line in stream::lines(reader) {
+        let (idx, line) = line?;
+
+        let width = *width.get_or_insert(line.len());
+        if width == 0 {
+            return Err(ParseError::new(3, idx + 1, 1, "a binary number").into());
+        }
 
-    for idx in 0..length {
-        let mc = pred(inp, idx);
-        result.push(mc);
+        check_line(idx, &line, width)?;
+        f(width, &line);
     }
 
-    from_binary(&result)
+    width.ok_or_else(|| ParseError::new(3, 1, 1, "a binary number").into())
 }
 
-#[aoc(day3, part1)]
-pub fn part1(inp: &[String]) -> i64 {
-    let gamma = calculate_rate(inp, most_common_at_idx);
-    let epsilon = calculate_rate(inp, least_common_at_idx);
+// Gamma takes the most common bit of every column, epsilon the least common.
+fn power_consumption(
+    width: usize,
+    len: usize,
+    ones_at: impl Fn(usize) -> usize,
+) -> Result<Count, Overflow> {
+    let most_common = (0..width)
+        .map(|idx| {
+            let ones = ones_at(idx);
+            len - ones <= ones
+        })
+        .collect::<Vec<_>>();
+
+    let gamma = to_count(width, |idx| most_common[idx])?;
+    let epsilon = to_count(width, |idx| !most_common[idx])?;
 
-    gamma * epsilon
+    gamma.checked_mul(&epsilon)
 }
 
-fn find_rating_value<P>(inp: &[String], pred: P) -> i64
-where
-    P: Fn(&[String], usize) -> char,
-{
-    let length = inp[0].len();
+#[aoc(day3, part1)]
+pub fn part1(inp: &Report) -> Result<Count, Overflow> {
+    power_consumption(inp.width(), inp.len(), |idx| inp.ones_at(idx))
+}
 
-    let mut current_list = inp.to_vec();
-    for idx in 0..length {
-        if current_list.len() == 1 {
+// The candidates sharing the bits chosen so far are a contiguous run of the
+// sorted rows, with those having a zero at the next position first. There is
+// no rating unless the criteria narrow them down to a single number.
+fn find_rating_value(inp: &Report, most_common: bool) -> Result<Count, Unanswered> {
+    let mut candidates = 0..inp.len();
+    for idx in 0..inp.width() {
+        if candidates.len() == 1 {
             break;
         }
 
-        let bit_to_find = pred(&current_list, idx);
-        current_list.retain(|it| it.chars().nth(idx).expect("idx is in range") == bit_to_find);
+        let first_one = inp.first_one(candidates.clone(), idx);
+        let zeros = first_one - candidates.start;
+        let ones = candidates.end - first_one;
+        let keep_ones = if most_common {
+            zeros <= ones
+        } else {
+            zeros > ones
+        };
+
+        candidates = if keep_ones {
+            first_one..candidates.end
+        } else {
+            candidates.start..first_one
+        };
+        trace::event!(
+            3,
+            "filter",
+            position = idx,
+            bit = if keep_ones { '1' } else { '0' },
+            remaining = candidates.len(),
+        );
     }
 
-    assert_eq!(current_list.len(), 1);
+    if candidates.len() != 1 {
+        return Err(Unanswered::Missing);
+    }
 
-    let result = &current_list[0];
-    from_binary(result)
+    let result = inp.row(candidates.start);
+    Ok(to_count(inp.width(), |idx| bit(result, idx))?)
 }
 
 #[aoc(day3, part2)]
-pub fn part2(inp: &[String]) -> i64 {
-    let oxygen = find_rating_value(inp, most_common_at_idx);
-    let co2 = find_rating_value(inp, least_common_at_idx);
+pub fn part2(inp: &Report) -> Result<Count, Unanswered> {
+    let oxygen = find_rating_value(inp, true)?;
+    let co2 = find_rating_value(inp, false)?;
+
+    Ok(oxygen.checked_mul(&co2)?)
+}
+
+// Only the count of ones in each column is kept.
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<Count, StreamError> {
+    let mut len = 0;
+    let mut ones = Vec::new();
+
+    let width = for_each_line(reader, |width, line| {
+        ones.resize(width, 0);
+        len += 1;
+
+        for (pos, _) in line.bytes().enumerate().filter(|(_, b)| *b == b'1') {
+            ones[pos] += 1;
+        }
+    })?;
 
-    oxygen * co2
+    Ok(power_consumption(width, len, |idx| ones[idx])?)
+}
+
+// The ratings need every number, so they are packed into a `Report` as they are
+// read, which takes less space than the lines themselves.
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<Count, StreamError> {
+    let mut report = None;
+    for_each_line(reader, |width, line| {
+        report.get_or_insert_with(|| Report::new(width)).push(line);
+    })?;
+
+    let report = report
+        .ok_or_else(|| ParseError::new(3, 1, 1, "a binary number"))?
+        .finish();
+    Ok(part2(&report)?)
 }
 
 #[cfg(test)]
@@ -105,15 +296,96 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
-        assert_eq!(res, 198);
+        assert_eq!(res, Ok(Count::from(198)));
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
-        assert_eq!(res, 230);
+        assert_eq!(res, Ok(Count::from(230)));
+    }
+
+    #[test]
+    fn test_no_single_rating() {
+        let Ok(gen) = generate("01\n01") else {
+            panic!("Could not parse test input")
+        };
+
+        let res = part2(&gen);
+        assert_eq!(res, Err(Unanswered::Missing));
+    }
+
+    #[test]
+    fn test_wide_report() {
+        let padded = TEST_DATA
+            .lines()
+            .map(|it| format!("{}{it}", "0".repeat(70)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Ok(gen) = generate(&padded) else {
+            panic!("Could not parse test input")
+        };
+
+        assert_eq!(gen.width(), 75);
+        assert_eq!(gen.ones_at(0), 0);
+        assert_eq!(gen.ones_at(70), 7);
+
+        // Epsilon has ones in all the padded columns.
+        let res = part1(&gen);
+        if cfg!(feature = "bigint") {
+            assert_eq!(
+                res.map(|it| it.to_string()).ok().as_deref(),
+                Some("831136500985057557609990")
+            );
+        } else {
+            assert_eq!(res, Err(Overflow));
+        }
+
+        let Ok(gen) = generate(&format!("1{}\n0{}", "0".repeat(69), "1".repeat(69))) else {
+            panic!("Could not parse test input")
+        };
+        let res = part2(&gen);
+        if cfg!(feature = "bigint") {
+            assert_eq!(
+                res.map(|it| it.to_string()).ok().as_deref(),
+                Some("348449143727040986585905302199771942879232")
+            );
+        } else {
+            assert_eq!(res, Err(Unanswered::Overflow));
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(
+            part1_streaming(TEST_DATA.as_bytes()).ok(),
+            Some(Count::from(198))
+        );
+        assert_eq!(
+            part2_streaming(TEST_DATA.as_bytes()).ok(),
+            Some(Count::from(230))
+        );
+
+        let wide = format!("1{}\n0{}\n", "0".repeat(69), "1".repeat(69));
+        let Ok(gen) = generate(&wide) else {
+            panic!("Could not parse test input")
+        };
+        assert_eq!(part1_streaming(wide.as_bytes()).ok(), part1(&gen).ok());
+        assert_eq!(part2_streaming(wide.as_bytes()).ok(), part2(&gen).ok());
+
+        let res = part1_streaming(&b"0101\n011"[..]);
+        assert!(matches!(
+            res,
+            Err(StreamError::Parse(err)) if err == ParseError::new(3, 2, 4, "a binary number with 4 digits")
+        ));
     }
 }
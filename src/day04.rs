@@ -1,5 +1,9 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+
+use aoc_utils::parse::{column_of, first_line, parse_list, ParseError};
+use aoc_utils::trace;
+
+const BOARD_SIZE: usize = 5;
 
 #[derive(Clone)]
 struct Board {
@@ -14,43 +18,70 @@ pub struct Bingo {
 }
 
 #[aoc_generator(day4)]
-pub fn generate(inp: &str) -> Bingo {
-    let mut lines = inp.lines();
-    let drawn_numbers = lines
-        .next()
-        .map(|it| {
-            it.split(',')
-                .filter_map(|it| it.parse::<i64>().ok())
-                .collect_vec()
-        })
-        .unwrap_or_default();
+pub fn generate(inp: &str) -> Result<Bingo, ParseError> {
+    let drawn_numbers = parse_list(
+        4,
+        1,
+        first_line(4, inp, "the drawn numbers")?,
+        ',',
+        "a drawn number",
+    )?;
 
     let mut boards = Vec::new();
+    let mut board = Vec::new();
+
+    for (idx, line) in inp.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            if !board.is_empty() {
+                boards.push(finish_board(std::mem::take(&mut board), idx)?);
+            }
+            continue;
+        }
+
+        let row = line
+            .split_whitespace()
+            .map(|it| {
+                it.parse::<i64>()
+                    .map(|num| (num, false))
+                    .map_err(|_| ParseError::new(4, idx + 1, column_of(line, it), "a board number"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if row.len() != BOARD_SIZE || board.len() == BOARD_SIZE {
+            return Err(ParseError::new(
+                4,
+                idx + 1,
+                1,
+                format!("a board of {BOARD_SIZE}x{BOARD_SIZE} numbers"),
+            ));
+        }
+        board.push(row);
+    }
 
-    let all_board_lines = lines.skip_while(|it| it.is_empty()).join("\n");
-    for s in all_board_lines.split("\n\n") {
-        let board_numbers = s.split('\n').collect_vec();
-
-        let board = board_numbers.iter().fold(Vec::new(), |mut acc, it| {
-            let nums = it
-                .split(' ')
-                .filter_map(|it| it.parse::<i64>().ok())
-                .zip(std::iter::repeat(false))
-                .collect_vec();
-            acc.push(nums);
-            acc
-        });
-
-        boards.push(Board {
-            numbers: board,
-            winning_number: None,
-        });
+    if !board.is_empty() {
+        boards.push(finish_board(board, inp.lines().count())?);
     }
 
-    Bingo {
+    Ok(Bingo {
         drawn_numbers,
         boards,
+    })
+}
+
+fn finish_board(numbers: Vec<Vec<(i64, bool)>>, idx: usize) -> Result<Board, ParseError> {
+    if numbers.len() != BOARD_SIZE {
+        return Err(ParseError::new(
+            4,
+            idx + 1,
+            1,
+            format!("a board of {BOARD_SIZE}x{BOARD_SIZE} numbers"),
+        ));
     }
+
+    Ok(Board {
+        numbers,
+        winning_number: None,
+    })
 }
 
 fn has_winning_row(board: &Board) -> bool {
@@ -61,7 +92,7 @@ fn has_winning_row(board: &Board) -> bool {
 }
 
 fn has_winning_col(board: &Board) -> bool {
-    (0..5).any(|col| board.numbers.iter().map(|it| it[col]).all(|(_, it)| it))
+    (0..BOARD_SIZE).any(|col| board.numbers.iter().map(|it| it[col]).all(|(_, it)| it))
 }
 
 fn is_winning(board: &Board) -> bool {
@@ -86,7 +117,7 @@ pub fn part1(inp: &Bingo) -> Option<i64> {
     let mut boards = inp.boards.clone();
 
     for &drawn_number in &inp.drawn_numbers {
-        for board in &mut boards {
+        for (idx, board) in boards.iter_mut().enumerate() {
             for row in &mut board.numbers {
                 for (num, flag) in row {
                     if *num == drawn_number {
@@ -97,6 +128,13 @@ pub fn part1(inp: &Bingo) -> Option<i64> {
 
             if is_winning(board) {
                 let unmarked = sum_unmarked(board);
+                trace::event!(
+                    4,
+                    "win",
+                    board = idx,
+                    number = drawn_number,
+                    unmarked = unmarked
+                );
                 return Some(drawn_number * unmarked);
             }
         }
@@ -112,7 +150,11 @@ pub fn part2(inp: &Bingo) -> Option<i64> {
     let mut winning_boards = Vec::new();
 
     for &drawn_number in &inp.drawn_numbers {
-        for board in boards.iter_mut().filter(|it| it.winning_number.is_none()) {
+        for (idx, board) in boards
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, it)| it.winning_number.is_none())
+        {
             for row in &mut board.numbers {
                 for (num, flag) in row {
                     if *num == drawn_number {
@@ -122,6 +164,13 @@ pub fn part2(inp: &Bingo) -> Option<i64> {
             }
 
             if is_winning(board) {
+                trace::event!(
+                    4,
+                    "win",
+                    board = idx,
+                    number = drawn_number,
+                    unmarked = sum_unmarked(board),
+                );
                 board.winning_number = Some(drawn_number);
                 winning_boards.push(board.clone());
             }
@@ -160,15 +209,36 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, Some(4512));
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, Some(1924));
     }
+
+    #[test]
+    fn test_rejects_incomplete_boards() {
+        let short_row = TEST_DATA.replacen("22 13 17 11  0", "22 13 17 11", 1);
+        assert_eq!(
+            generate(&short_row).err(),
+            Some(ParseError::new(4, 3, 1, "a board of 5x5 numbers"))
+        );
+
+        let short_board = TEST_DATA.replacen("\n1 12 20 15 19", "", 1);
+        assert_eq!(
+            generate(&short_board).err(),
+            Some(ParseError::new(4, 7, 1, "a board of 5x5 numbers"))
+        );
+    }
 }
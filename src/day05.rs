@@ -1,87 +1,107 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+use itertools::process_results;
 use parse_display::{Display, FromStr};
-use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::io::BufRead;
 
-#[derive(Display, FromStr, Copy, Clone)]
-#[display("{x},{y}")]
-pub struct Point {
-    x: i64,
-    y: i64,
-}
+use aoc_utils::parse::{parse_line, ParseError};
+use aoc_utils::stream::{self, StreamError};
+use aoc_utils::trace;
+use aoc_utils::vector::Vec2;
+
+const EXPECTED: &str = "a vent line like `0,9 -> 5,9`";
 
 #[derive(Display, FromStr, Copy, Clone)]
 #[display("{from} -> {to}")]
 pub struct Line {
-    from: Point,
-    to: Point,
+    from: Vec2<i64>,
+    to: Vec2<i64>,
 }
 
 impl Line {
-    fn points_on_line(&self) -> Vec<(i64, i64)> {
-        let x_dir = match self.from.x.cmp(&self.to.x) {
-            Ordering::Less => 1,
-            Ordering::Equal => 0,
-            Ordering::Greater => -1,
-        };
+    fn points_on_line(&self) -> Vec<Vec2<i64>> {
+        let dir = (self.to - self.from).signum();
 
-        let y_dir = match self.from.y.cmp(&self.to.y) {
-            Ordering::Less => 1,
-            Ordering::Equal => 0,
-            Ordering::Greater => -1,
-        };
+        let mut cur = self.from;
+        let mut result = vec![cur];
 
-        let mut cur_x = self.from.x;
-        let mut cur_y = self.from.y;
+        while cur != self.to {
+            cur += dir;
+            result.push(cur);
+        }
 
-        let mut result = vec![(cur_x, cur_y)];
+        result
+    }
 
-        while cur_x != self.to.x || cur_y != self.to.y {
-            cur_x += x_dir;
-            cur_y += y_dir;
+    const fn is_axis_aligned(&self) -> bool {
+        self.from.x == self.to.x || self.from.y == self.to.y
+    }
 
-            result.push((cur_x, cur_y));
-        }
+    const fn is_diagonal(&self) -> bool {
+        self.from.x.abs_diff(self.to.x) == self.from.y.abs_diff(self.to.y)
+    }
+}
 
-        result
+// Stepping towards the end of any other line would never reach it.
+fn parse_vent(idx: usize, line: &str) -> Result<Line, ParseError> {
+    let vent = parse_line::<Line>(5, idx, line, EXPECTED)?;
+    if !vent.is_axis_aligned() && !vent.is_diagonal() {
+        return Err(ParseError::new(
+            5,
+            idx + 1,
+            1,
+            "a horizontal, vertical or diagonal line",
+        ));
     }
+
+    Ok(vent)
 }
 
 #[aoc_generator(day5)]
-pub fn generate(inp: &str) -> Vec<Line> {
+pub fn generate(inp: &str) -> Result<Vec<Line>, ParseError> {
     inp.lines()
-        .filter_map(|it| it.parse::<Line>().ok())
+        .enumerate()
+        .map(|(idx, line)| parse_vent(idx, line))
         .collect()
 }
 
-fn count_intersections(lines: &[Line]) -> usize {
-    lines
-        .iter()
-        .flat_map(Line::points_on_line)
-        .fold(HashMap::new(), |mut acc, (x, y)| {
-            acc.entry((x, y)).and_modify(|it| *it += 1).or_insert(1);
-            acc
-        })
-        .iter()
-        .filter(|(_, &count)| count > 1)
-        .count()
+// Memory is bounded by the number of covered points, not by the number of lines.
+fn count_intersections(lines: impl Iterator<Item = Line>) -> usize {
+    let covered =
+        lines
+            .flat_map(|it| it.points_on_line())
+            .fold(HashMap::new(), |mut acc, point| {
+                acc.entry(point).and_modify(|it| *it += 1).or_insert(1);
+                acc
+            });
+
+    let overlaps = covered.values().filter(|&&count| count > 1).count();
+    trace::event!(5, "overlaps", points = covered.len(), overlaps = overlaps);
+    overlaps
+}
+
+fn read_lines<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Line, StreamError>> {
+    stream::parse_lines(reader, parse_vent)
 }
 
 #[aoc(day5, part1)]
 pub fn part1(inp: &[Line]) -> usize {
-    let inp = inp
-        .iter()
-        .filter(|it| it.from.x == it.to.x || it.from.y == it.to.y)
-        .copied()
-        .collect_vec();
-
-    count_intersections(&inp)
+    count_intersections(inp.iter().filter(|it| it.is_axis_aligned()).copied())
 }
 
 #[aoc(day5, part2)]
 pub fn part2(inp: &[Line]) -> usize {
-    count_intersections(inp)
+    count_intersections(inp.iter().copied())
+}
+
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<usize, StreamError> {
+    process_results(read_lines(reader), |lines| {
+        count_intersections(lines.filter(Line::is_axis_aligned))
+    })
+}
+
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<usize, StreamError> {
+    process_results(read_lines(reader), |lines| count_intersections(lines))
 }
 
 #[cfg(test)]
@@ -101,15 +121,48 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 5);
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 12);
     }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(part1_streaming(TEST_DATA.as_bytes()).ok(), Some(5));
+        assert_eq!(part2_streaming(TEST_DATA.as_bytes()).ok(), Some(12));
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let res = generate("0,9 -> 5,9\n8,0 => 0,8");
+        assert_eq!(
+            res.err(),
+            Some(ParseError::new(5, 2, 1, "a vent line like `0,9 -> 5,9`"))
+        );
+    }
+
+    #[test]
+    fn test_rejects_skewed_lines() {
+        let expected = ParseError::new(5, 2, 1, "a horizontal, vertical or diagonal line");
+        let inp = "0,9 -> 5,9\n0,0 -> 3,1";
+
+        assert_eq!(generate(inp).err(), Some(expected.clone()));
+        assert!(matches!(
+            part2_streaming(inp.as_bytes()),
+            Err(StreamError::Parse(err)) if err == expected
+        ));
+    }
 }
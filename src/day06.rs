@@ -1,39 +1,48 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use aoc_runner_derive::{aoc, aoc_generator};
 
-#[aoc_generator(day6)]
-pub fn generate(inp: &str) -> Option<Vec<usize>> {
-    Some(
-        inp.lines()
-            .next()?
-            .split(',')
-            .filter_map(|it| it.parse().ok())
-            .collect(),
-    )
+use aoc_utils::count::{Count, Overflow};
+use aoc_utils::parse::{column_of, first_line, parse_list, ParseError};
+use aoc_utils::trace;
+
+const EXPECTED: &str = "a timer from 0 to 8";
+
+#[cfg_attr(feature = "std", aoc_generator(day6))]
+pub fn generate(inp: &str) -> Result<Vec<usize>, ParseError> {
+    let line = first_line(6, inp, "a comma-separated list")?;
+    let fish = parse_list(6, 1, line, ',', EXPECTED)?;
+
+    if let Some((token, _)) = line.split(',').zip(&fish).find(|(_, &it)| it > 8) {
+        return Err(ParseError::new(6, 1, column_of(line, token), EXPECTED));
+    }
+
+    Ok(fish)
 }
 
-fn simulate_fish(num_days: u16, fish: &[usize]) -> usize {
-    let mut result = [0; 9];
+pub fn simulate_fish(num_days: u16, fish: &[usize]) -> Result<Count, Overflow> {
+    let mut result = core::array::from_fn::<Count, 9, _>(|_| Count::zero());
 
     for f in fish {
-        result[*f] += 1;
+        result[*f] = result[*f].checked_add(&Count::from(1))?;
     }
 
-    for _ in 0..num_days {
-        let new_fish = result[0];
+    for day in 1..=num_days {
         result.rotate_left(1);
-        result[6] += new_fish;
+        result[6] = result[6].checked_add(&result[8])?;
+        trace::event!(6, "day", day = day, timers = result.to_vec());
     }
 
-    result.iter().sum::<usize>()
+    Count::sum(&result)
 }
 
-#[aoc(day6, part1)]
-pub fn part1(inp: &[usize]) -> usize {
+#[cfg_attr(feature = "std", aoc(day6, part1))]
+pub fn part1(inp: &[usize]) -> Result<Count, Overflow> {
     simulate_fish(80, inp)
 }
 
-#[aoc(day6, part2)]
-pub fn part2(inp: &[usize]) -> usize {
+#[cfg_attr(feature = "std", aoc(day6, part2))]
+pub fn part2(inp: &[usize]) -> Result<Count, Overflow> {
     simulate_fish(256, inp)
 }
 
@@ -45,21 +54,47 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let Some(gen) = generate(TEST_DATA) else {
+        let Ok(gen) = generate(TEST_DATA) else {
             panic!("Could not parse test input")
         };
 
         let res = part1(&gen);
-        assert_eq!(res, 5934);
+        assert_eq!(res, Ok(Count::from(5934)));
     }
 
     #[test]
     fn test_sample_p2() {
-        let Some(gen) = generate(TEST_DATA) else {
+        let Ok(gen) = generate(TEST_DATA) else {
             panic!("Could not parse test input")
         };
 
         let res = part2(&gen);
-        assert_eq!(res, 26_984_457_539);
+        assert_eq!(res, Ok(Count::from(26_984_457_539)));
+    }
+
+    #[test]
+    fn test_rejects_invalid_timers() {
+        assert_eq!(generate("9,1"), Err(ParseError::new(6, 1, 1, EXPECTED)));
+        assert_eq!(generate("3,4,10"), Err(ParseError::new(6, 1, 5, EXPECTED)));
+        assert_eq!(generate("3,x"), Err(ParseError::new(6, 1, 3, EXPECTED)));
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        let res = simulate_fish(10_000, &gen);
+        if cfg!(feature = "bigint") {
+            let Ok(count) = res else {
+                panic!("Counts are exact with `bigint`")
+            };
+            let count = count.to_string();
+            assert_eq!(count.len(), 380);
+            assert!(count.starts_with("12295220913725202160"));
+        } else {
+            assert_eq!(res, Err(Overflow));
+        }
     }
 }
@@ -1,15 +1,31 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-#[aoc_generator(day7)]
-pub fn generate(inp: &str) -> Option<Vec<i64>> {
-    Some(
-        inp.lines()
-            .next()?
-            .split(',')
-            .filter_map(|it| it.parse().ok())
-            .collect(),
-    )
+use aoc_utils::parse::{column_of, first_line, parse_list, ParseError};
+use aoc_utils::trace;
+
+// Every position between the outermost crabs is tried as a target.
+const MAX_POSITION: i64 = 10_000;
+const EXPECTED: &str = "a crab position from 0 to 10000";
+
+#[cfg_attr(feature = "std", aoc_generator(day7))]
+pub fn generate(inp: &str) -> Result<Vec<i64>, ParseError> {
+    let line = first_line(7, inp, "a comma-separated list")?;
+    let crabs = parse_list(7, 1, line, ',', EXPECTED)?;
+
+    if let Some((token, _)) = line
+        .split(',')
+        .zip(&crabs)
+        .find(|(_, it)| !(0..=MAX_POSITION).contains(*it))
+    {
+        return Err(ParseError::new(7, 1, column_of(line, token), EXPECTED));
+    }
+
+    Ok(crabs)
 }
 
 fn fuel_for_target<T>(target: i64, inp: &[i64], fuel_cost: T) -> i64
@@ -22,20 +38,31 @@ where
     })
 }
 
-#[aoc(day7, part1)]
-pub fn part1(inp: &[i64]) -> Option<i64> {
+fn min_fuel<T>(inp: &[i64], fuel_cost: T) -> Option<i64>
+where
+    T: Fn(i64) -> i64 + Sync,
+{
     let (&min_pos, &max_pos) = inp.iter().minmax().into_option()?;
-    (min_pos..=max_pos)
-        .map(|it| fuel_for_target(it, inp, |it| it))
-        .min()
+
+    let targets = min_pos..=max_pos;
+    #[cfg(feature = "parallel")]
+    let targets = targets.into_par_iter();
+
+    let (fuel, target) = targets
+        .map(|it| (fuel_for_target(it, inp, &fuel_cost), it))
+        .min()?;
+    trace::event!(7, "alignment", target = target, fuel = fuel);
+    Some(fuel)
+}
+
+#[cfg_attr(feature = "std", aoc(day7, part1))]
+pub fn part1(inp: &[i64]) -> Option<i64> {
+    min_fuel(inp, |it| it)
 }
 
-#[aoc(day7, part2)]
+#[cfg_attr(feature = "std", aoc(day7, part2))]
 pub fn part2(inp: &[i64]) -> Option<i64> {
-    let (&min_pos, &max_pos) = inp.iter().minmax().into_option()?;
-    (min_pos..=max_pos)
-        .map(|it| fuel_for_target(it, inp, |it| it * (it + 1) / 2))
-        .min()
+    min_fuel(inp, |it| it * (it + 1) / 2)
 }
 
 #[cfg(test)]
@@ -46,7 +73,7 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let Some(gen) = generate(TEST_DATA) else {
+        let Ok(gen) = generate(TEST_DATA) else {
             panic!("Could not parse test input")
         };
 
@@ -56,11 +83,21 @@ mod tests {
 
     #[test]
     fn test_sample_p2() {
-        let Some(gen) = generate(TEST_DATA) else {
+        let Ok(gen) = generate(TEST_DATA) else {
             panic!("Could not parse test input")
         };
 
         let res = part2(&gen);
         assert_eq!(res, Some(168));
     }
+
+    #[test]
+    fn test_rejects_distant_crabs() {
+        assert_eq!(
+            generate("0,9223372036854775807"),
+            Err(ParseError::new(7, 1, 3, EXPECTED))
+        );
+        assert_eq!(generate("-1,2"), Err(ParseError::new(7, 1, 1, EXPECTED)));
+        assert_eq!(generate("0,10000").map(|it| it.len()), Ok(2));
+    }
 }
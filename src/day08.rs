@@ -2,28 +2,59 @@ use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use std::collections::HashSet;
 
+use aoc_utils::parse::ParseError;
+use aoc_utils::trace;
+
 #[derive(Debug, Clone)]
 pub struct SignalRelation {
     inputs: Vec<String>,
     outputs: Vec<String>,
 }
 
+const PATTERNS: usize = 10;
+const OUTPUTS: usize = 4;
+
 #[aoc_generator(day8)]
-pub fn generate(inp: &str) -> Vec<SignalRelation> {
+pub fn generate(inp: &str) -> Result<Vec<SignalRelation>, ParseError> {
     inp.lines()
-        .map(|it| {
-            let spl = it.split('|').collect_vec();
-            let inputs = spl[0]
-                .split(' ')
-                .filter(|it| !it.is_empty())
-                .filter_map(|l| l.parse::<String>().ok())
+        .enumerate()
+        .map(|(idx, it)| {
+            let (inputs, outputs) = it.split_once('|').ok_or_else(|| {
+                ParseError::new(
+                    8,
+                    idx + 1,
+                    it.chars().count() + 1,
+                    "`|` before the output digits",
+                )
+            })?;
+            let inputs = inputs
+                .split_whitespace()
+                .map(ToString::to_string)
                 .collect_vec();
-            let outputs = spl[1]
-                .split(' ')
-                .filter(|it| !it.is_empty())
-                .filter_map(|l| l.parse::<String>().ok())
+            if inputs.len() != PATTERNS {
+                return Err(ParseError::new(
+                    8,
+                    idx + 1,
+                    1,
+                    format!("{PATTERNS} signal patterns"),
+                ));
+            }
+
+            let column = it.chars().count() - outputs.chars().count() + 1;
+            let outputs = outputs
+                .split_whitespace()
+                .map(ToString::to_string)
                 .collect_vec();
-            SignalRelation { inputs, outputs }
+            if outputs.len() != OUTPUTS {
+                return Err(ParseError::new(
+                    8,
+                    idx + 1,
+                    column,
+                    format!("{OUTPUTS} output digits"),
+                ));
+            }
+
+            Ok(SignalRelation { inputs, outputs })
         })
         .collect()
 }
@@ -41,26 +72,26 @@ pub fn part1(inp: &[SignalRelation]) -> usize {
         })
 }
 
-fn find_digit_by_len(us: &[String], len: usize) -> HashSet<char> {
+fn find_digit_by_len(us: &[String], len: usize) -> Option<HashSet<char>> {
     us.iter()
         .find(|it| it.len() == len)
-        .expect("Element with length 'len' exists")
-        .chars()
-        .collect::<HashSet<_>>()
+        .map(|it| it.chars().collect::<HashSet<_>>())
 }
 
+// Gives no answer when the patterns of an entry are not the ten digits.
 #[aoc(day8, part2)]
-pub fn part2(inp: &[SignalRelation]) -> usize {
+pub fn part2(inp: &[SignalRelation]) -> Option<usize> {
     inp.iter()
-        .map(|it| {
+        .enumerate()
+        .map(|(entry, it)| {
             let mut digits = vec![HashSet::new(); 10];
 
             let inputs = &it.inputs;
 
-            digits[1] = find_digit_by_len(inputs, 2);
-            digits[4] = find_digit_by_len(inputs, 4);
-            digits[7] = find_digit_by_len(inputs, 3);
-            digits[8] = find_digit_by_len(inputs, 7);
+            digits[1] = find_digit_by_len(inputs, 2)?;
+            digits[4] = find_digit_by_len(inputs, 4)?;
+            digits[7] = find_digit_by_len(inputs, 3)?;
+            digits[8] = find_digit_by_len(inputs, 7)?;
 
             // len 6 => 0, 6, 9
             inputs
@@ -92,14 +123,13 @@ pub fn part2(inp: &[SignalRelation]) -> usize {
                     }
                 });
 
-            it.outputs.iter().fold(0, |acc, it| {
+            let value = it.outputs.iter().try_fold(0, |acc, it| {
                 let sig = it.chars().collect::<HashSet<_>>();
-                let as_num = digits
-                    .iter()
-                    .position(|d| *d == sig)
-                    .expect("Element 'sig' exists");
-                acc * 10 + as_num
-            })
+                let as_num = digits.iter().position(|d| *d == sig)?;
+                Some(acc * 10 + as_num)
+            })?;
+            trace::event!(8, "decoded", entry = entry, value = value);
+            Some(value)
         })
         .sum()
 }
@@ -121,15 +151,38 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 26);
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
-        assert_eq!(res, 61_229);
+        assert_eq!(res, Some(61_229));
+    }
+
+    #[test]
+    fn test_rejects_malformed_entries() {
+        assert_eq!(
+            generate("ab | cd").err(),
+            Some(ParseError::new(8, 1, 1, "10 signal patterns"))
+        );
+        assert_eq!(
+            generate("a b c d e f g h i j | cd").err(),
+            Some(ParseError::new(8, 1, 22, "4 output digits"))
+        );
+
+        let Ok(gen) = generate("ab ab ab ab ab ab ab ab ab ab | ab ab ab cd") else {
+            panic!("Could not parse test input")
+        };
+        assert_eq!(part2(&gen), None);
     }
 }
@@ -1,92 +1,43 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::{iproduct, Itertools};
-use std::collections::HashSet;
+use itertools::Itertools;
+
+use aoc_utils::grid::Grid;
+use aoc_utils::parse::ParseError;
+use aoc_utils::search;
+use aoc_utils::trace;
 
 #[aoc_generator(day9)]
-pub fn generate(inp: &str) -> Vec<Vec<usize>> {
-    inp.lines()
-        .map(|it| {
-            it.chars()
-                .map(|c| c.to_string().parse::<usize>().expect("Is digit"))
-                .collect()
-        })
-        .collect()
+pub fn generate(inp: &str) -> Result<Grid<usize>, ParseError> {
+    Grid::parse_digits(9, inp)
 }
 
-fn get_neighbour_coords(x: usize, y: usize, inp: &[Vec<usize>]) -> Vec<(usize, usize)> {
-    let mut neighbours = vec![(x + 1, y), (x, y + 1)];
-    if let Some(it) = x.checked_sub(1) {
-        neighbours.push((it, y));
-    }
-    if let Some(it) = y.checked_sub(1) {
-        neighbours.push((x, it));
-    }
-
-    let width = inp.len();
-    let height = inp[0].len();
-
-    neighbours
-        .iter()
-        .copied()
-        .filter(|&(dx, dy)| dx < width && dy < height)
-        .collect()
+fn is_lowpoint(pos: (usize, usize), inp: &Grid<usize>) -> bool {
+    inp.neighbours4(pos).all(|it| inp[it] > inp[pos])
 }
 
-fn is_lowpoint(candidate: usize, x: usize, y: usize, inp: &[Vec<usize>]) -> bool {
-    get_neighbour_coords(x, y, inp)
-        .into_iter()
-        .all(|(nx, ny)| inp[nx][ny] > candidate)
-}
-
-fn get_lowpoints(inp: &[Vec<usize>]) -> Vec<(usize, usize)> {
-    let width = inp.len();
-    let height = inp[0].len();
-
-    let mut low_points = Vec::new();
-
-    for (x, y) in iproduct!(0..width, 0..height) {
-        let cur = inp[x][y];
-        if is_lowpoint(cur, x, y, inp) {
-            low_points.push((x, y));
-        }
-    }
-
-    low_points
+fn get_lowpoints(inp: &Grid<usize>) -> Vec<(usize, usize)> {
+    inp.positions().filter(|&it| is_lowpoint(it, inp)).collect()
 }
 
 #[aoc(day9, part1)]
-pub fn part1(inp: &[Vec<usize>]) -> usize {
+pub fn part1(inp: &Grid<usize>) -> usize {
     get_lowpoints(inp)
         .into_iter()
-        .fold(0, |acc, (x, y)| acc + 1 + inp[x][y])
+        .fold(0, |acc, pos| acc + 1 + inp[pos])
 }
 
-fn get_basin_size(x: usize, y: usize, inp: &[Vec<usize>]) -> usize {
-    let mut basin = HashSet::new();
-
-    let mut queue = vec![(x, y)];
-
-    while let Some((x, y)) = queue.pop() {
-        let neighbours = get_neighbour_coords(x, y, inp)
-            .into_iter()
-            .filter(|&(nx, ny)| inp[nx][ny] != 9)
-            .collect_vec();
-
-        for (nx, ny) in neighbours {
-            if basin.insert((nx, ny)) {
-                queue.push((nx, ny));
-            }
-        }
-    }
+fn get_basin_size(pos: (usize, usize), inp: &Grid<usize>) -> usize {
+    let basin = search::flood_fill(pos, |&it| inp.neighbours4(it).filter(|&n| inp[n] != 9));
 
+    trace::event!(9, "basin", low_point = pos, size = basin.len());
     basin.len()
 }
 
 #[aoc(day9, part2)]
-pub fn part2(inp: &[Vec<usize>]) -> usize {
+pub fn part2(inp: &Grid<usize>) -> usize {
     get_lowpoints(inp)
         .into_iter()
-        .map(|(x, y)| get_basin_size(x, y, inp))
+        .map(|pos| get_basin_size(pos, inp))
         .sorted()
         .rev()
         .take(3)
@@ -105,15 +56,27 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 15);
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 1_134);
     }
+
+    #[test]
+    fn test_invalid_height() {
+        let res = generate("2199943210\n39878949x1");
+        assert_eq!(res, Err(ParseError::new(9, 2, 9, "a digit")));
+    }
 }
@@ -1,9 +1,20 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+
+use aoc_utils::parse::ParseError;
+use aoc_utils::trace;
 
 #[aoc_generator(day10)]
-pub fn generate(inp: &str) -> Vec<String> {
-    inp.lines().map(ToString::to_string).collect()
+pub fn generate(inp: &str) -> Result<Vec<String>, ParseError> {
+    inp.lines()
+        .enumerate()
+        .map(|(idx, it)| {
+            if let Some(pos) = it.chars().position(|c| !"()[]{}<>".contains(c)) {
+                return Err(ParseError::new(10, idx + 1, pos + 1, "a bracket"));
+            }
+
+            Ok(it.to_string())
+        })
+        .collect()
 }
 
 #[derive(Default)]
@@ -50,6 +61,7 @@ fn syntax_error_score(s: &str) -> ParsingState {
             ')' | ']' | '}' | '>' => {
                 if let Some(expected) = stack.pop() {
                     if expected != chr {
+                        trace::event!(10, "corrupted", expected = expected, found = chr);
                         return ParsingState {
                             score: score_error(chr),
                             incomplete: false,
@@ -77,30 +89,29 @@ pub fn part1(inp: &[String]) -> usize {
     })
 }
 
+// Gives no answer without a middle score, or when a completion score does not fit.
 #[aoc(day10, part2)]
-pub fn part2(inp: &[String]) -> usize {
-    let scores = inp
+pub fn part2(inp: &[String]) -> Option<usize> {
+    let mut scores = inp
         .iter()
         .filter_map(|it| {
             let ParsingState {
                 incomplete, stack, ..
             } = syntax_error_score(it);
-            if incomplete {
-                let score = stack
-                    .iter()
-                    .rev()
-                    .fold(0, |acc, it| 5 * acc + score_completion(*it));
-                Some(score)
-            } else {
-                None
-            }
+            incomplete.then(|| {
+                stack.iter().rev().try_fold(0_usize, |acc, it| {
+                    acc.checked_mul(5)?.checked_add(score_completion(*it))
+                })
+            })
         })
-        .sorted_unstable()
-        .collect_vec();
+        .collect::<Option<Vec<_>>>()?;
+    scores.sort_unstable();
 
-    assert_eq!(scores.len() % 2, 1);
+    if scores.len() % 2 == 0 {
+        return None;
+    }
 
-    scores[scores.len() / 2]
+    Some(scores[scores.len() / 2])
 }
 
 #[cfg(test)]
@@ -120,15 +131,35 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 26_397);
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
-        assert_eq!(res, 288_957);
+        assert_eq!(res, Some(288_957));
+    }
+
+    #[test]
+    fn test_no_middle_score() {
+        let Ok(gen) = generate("()\n((\n[[") else {
+            panic!("Could not parse test input")
+        };
+        assert_eq!(part2(&gen[..1]), None);
+        assert_eq!(part2(&gen), None);
+
+        let Ok(gen) = generate(&"(".repeat(40)) else {
+            panic!("Could not parse test input")
+        };
+        assert_eq!(part2(&gen), None);
     }
 }
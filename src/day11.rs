@@ -1,66 +1,39 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+
+use aoc_utils::grid::Grid;
+use aoc_utils::parse::ParseError;
+use aoc_utils::trace;
 
 #[aoc_generator(day11)]
-pub fn generate(inp: &str) -> Vec<Vec<usize>> {
-    inp.lines()
-        .map(|it| {
-            it.chars()
-                .filter_map(|c| c.to_digit(10).map(|d| d as usize))
-                .collect_vec()
-        })
-        .collect()
+pub fn generate(inp: &str) -> Result<Grid<usize>, ParseError> {
+    Grid::parse_digits(11, inp)
 }
 
-fn apply_flash(row: usize, col: usize, inp: &mut [Vec<usize>]) {
-    let mut positions_to_check = vec![(row + 1, col), (row, col + 1), (row + 1, col + 1)];
-
-    if row >= 1 && col >= 1 {
-        positions_to_check.push((row - 1, col - 1));
-    }
-    if row >= 1 {
-        positions_to_check.push((row - 1, col));
-        positions_to_check.push((row - 1, col + 1));
-    }
-    if col >= 1 {
-        positions_to_check.push((row, col - 1));
-        positions_to_check.push((row + 1, col - 1));
-    }
-
-    let width = inp.len();
-    let height = inp[0].len();
-
-    for &(dx, dy) in positions_to_check
-        .iter()
-        .filter(|(dx, dy)| *dx < width && *dy < height)
-    {
-        if inp[dx][dy] != 0 {
-            inp[dx][dy] += 1;
+fn apply_flash(pos: (usize, usize), inp: &mut Grid<usize>) {
+    for neighbour in inp.neighbours8(pos) {
+        if inp[neighbour] != 0 {
+            inp[neighbour] += 1;
         }
     }
 }
 
-fn needs_to_flash(inp: &[Vec<usize>]) -> bool {
-    inp.iter().any(|r| r.iter().any(|c| *c > 9))
+fn needs_to_flash(inp: &Grid<usize>) -> bool {
+    inp.iter().any(|it| *it > 9)
 }
 
-fn do_step(inp: &mut [Vec<usize>]) -> usize {
+pub(crate) fn do_step(inp: &mut Grid<usize>) -> usize {
     let mut flashes = 0;
 
-    for row in inp.iter_mut() {
-        for col in row {
-            *col += 1;
-        }
+    for it in inp.iter_mut() {
+        *it += 1;
     }
 
     while needs_to_flash(inp) {
-        for row in 0..inp.len() {
-            for col in 0..inp[row].len() {
-                if inp[row][col] > 9 {
-                    inp[row][col] = 0;
-                    apply_flash(row, col, inp);
-                    flashes += 1;
-                }
+        for pos in inp.positions() {
+            if inp[pos] > 9 {
+                inp[pos] = 0;
+                apply_flash(pos, inp);
+                flashes += 1;
             }
         }
     }
@@ -68,34 +41,39 @@ fn do_step(inp: &mut [Vec<usize>]) -> usize {
     flashes
 }
 
-fn run_for_n_steps(n: usize, inp: &[Vec<usize>]) -> usize {
-    let mut next = inp.to_vec();
-    (0..n).fold(0, |acc, _| acc + do_step(&mut next))
+fn run_for_n_steps(n: usize, inp: &Grid<usize>) -> usize {
+    let mut next = inp.clone();
+    (1..=n).fold(0, |acc, step| {
+        let flashes = do_step(&mut next);
+        trace::event!(11, "step", step = step, flashes = flashes);
+        acc + flashes
+    })
 }
 
-fn all_flashed(inp: &[Vec<usize>]) -> bool {
-    inp.iter().all(|r| r.iter().all(|c| *c == 0))
+pub(crate) fn all_flashed(inp: &Grid<usize>) -> bool {
+    inp.iter().all(|it| *it == 0)
 }
 
-fn run_until_all_flash(inp: &[Vec<usize>]) -> usize {
-    let mut next = inp.to_vec();
+fn run_until_all_flash(inp: &Grid<usize>) -> usize {
+    let mut next = inp.clone();
     let mut step = 0;
 
     while !all_flashed(&next) {
-        let _ = do_step(&mut next);
+        let flashes = do_step(&mut next);
         step += 1;
+        trace::event!(11, "step", step = step, flashes = flashes);
     }
 
     step
 }
 
 #[aoc(day11, part1)]
-pub fn part1(inp: &[Vec<usize>]) -> usize {
+pub fn part1(inp: &Grid<usize>) -> usize {
     run_for_n_steps(100, inp)
 }
 
 #[aoc(day11, part2)]
-pub fn part2(inp: &[Vec<usize>]) -> usize {
+pub fn part2(inp: &Grid<usize>) -> usize {
     run_until_all_flash(inp)
 }
 
@@ -116,14 +94,20 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = run_for_n_steps(100, &gen);
         assert_eq!(res, 1656);
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 195);
     }
@@ -1,6 +1,9 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use parse_display::{Display, FromStr};
 
+use aoc_utils::parse::{parse_lines, ParseError};
+use aoc_utils::trace;
+
 #[derive(Display, FromStr, Eq, PartialEq, Clone, Debug)]
 #[display("{from}-{to}")]
 pub struct Path {
@@ -8,23 +11,40 @@ pub struct Path {
     to: String,
 }
 
+fn is_big(cave: &str) -> bool {
+    !cave.chars().all(char::is_lowercase)
+}
+
 #[aoc_generator(day12)]
-pub fn generate(inp: &str) -> Vec<Path> {
-    inp.lines()
-        .filter_map(|it| it.parse::<Path>().ok())
-        .fold(Vec::new(), |mut acc, it| {
-            if it.from != "start" && it.to != "end" {
-                let inverted = Path {
-                    from: it.to.clone(),
-                    to: it.from.clone(),
-                };
-                acc.push(inverted);
-            }
-
-            acc.push(it);
-
-            acc
-        })
+pub fn generate(inp: &str) -> Result<Vec<Path>, ParseError> {
+    let paths = parse_lines::<Path>(12, inp, "a path like `start-A`")?;
+
+    // Two connected big caves would allow infinitely many paths.
+    if let Some(idx) = paths
+        .iter()
+        .position(|it| is_big(&it.from) && is_big(&it.to))
+    {
+        return Err(ParseError::new(
+            12,
+            idx + 1,
+            1,
+            "a path to or from a small cave",
+        ));
+    }
+
+    Ok(paths.into_iter().fold(Vec::new(), |mut acc, it| {
+        if it.from != "start" && it.to != "end" {
+            let inverted = Path {
+                from: it.to.clone(),
+                to: it.from.clone(),
+            };
+            acc.push(inverted);
+        }
+
+        acc.push(it);
+
+        acc
+    }))
 }
 
 fn collect_paths_from<'a>(
@@ -43,7 +63,7 @@ fn collect_paths_from<'a>(
     let can_double = can_double
         && cur_path
             .iter()
-            .filter(|&&it| it == cur && it.chars().all(char::is_lowercase))
+            .filter(|&&it| it == cur && !is_big(it))
             .count()
             < 2;
 
@@ -86,13 +106,14 @@ fn find_paths(
         can_visit,
     );
 
+    trace::event!(12, "paths", can_double = can_double, count = result.len());
     result
 }
 
 #[aoc(day12, part1)]
 pub fn part1(inp: &[Path]) -> usize {
     let can_visit_cave = |node: &str, paths: &[&String], _: bool| {
-        !node.chars().all(char::is_lowercase) || !paths.iter().any(|&it| it == node)
+        is_big(node) || !paths.iter().any(|&it| it == node)
     };
 
     let paths = find_paths(inp, false, can_visit_cave);
@@ -106,7 +127,7 @@ pub fn part2(inp: &[Path]) -> usize {
             return false;
         }
 
-        if !node.chars().all(char::is_lowercase) {
+        if is_big(node) {
             return true;
         }
 
@@ -161,31 +182,57 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 10);
 
-        let gen = generate(TEST_DATA_MED);
+        let Ok(gen) = generate(TEST_DATA_MED) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 19);
 
-        let gen = generate(TEST_DATA_LARGE);
+        let Ok(gen) = generate(TEST_DATA_LARGE) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 226);
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 36);
 
-        let gen = generate(TEST_DATA_MED);
+        let Ok(gen) = generate(TEST_DATA_MED) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 103);
 
-        let gen = generate(TEST_DATA_LARGE);
+        let Ok(gen) = generate(TEST_DATA_LARGE) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 3509);
     }
+
+    #[test]
+    fn test_rejects_connected_big_caves() {
+        assert_eq!(
+            generate("start-A\nA-B\nB-end"),
+            Err(ParseError::new(12, 2, 1, "a path to or from a small cave"))
+        );
+    }
 }
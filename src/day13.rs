@@ -1,14 +1,13 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
 use parse_display::{Display, FromStr};
 use std::collections::HashSet;
 
-#[derive(Display, FromStr, Eq, PartialEq, Copy, Clone, Debug)]
-#[display("{x},{y}")]
-pub struct Point {
-    x: usize,
-    y: usize,
-}
+use aoc_utils::grid::Grid;
+use aoc_utils::parse::ParseError;
+use aoc_utils::trace;
+use aoc_utils::vector::Vec2;
+
+pub type Point = Vec2<usize>;
 
 #[derive(Display, FromStr, Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Fold {
@@ -26,104 +25,91 @@ pub struct Input {
 }
 
 #[aoc_generator(day13)]
-pub fn generate(inp: &str) -> Option<Input> {
-    let mut spl = inp.split("\n\n");
-    let first = spl.next()?;
-    let second = spl.next()?;
-
-    let points = first
-        .lines()
-        .filter_map(|it| it.parse::<Point>().ok())
-        .collect_vec();
-    let folds = second
-        .lines()
-        .filter_map(|it| it.parse::<Fold>().ok())
-        .collect_vec();
-
-    Some(Input { points, folds })
-}
+pub fn generate(inp: &str) -> Result<Input, ParseError> {
+    let mut lines = inp.lines().enumerate();
 
-fn fold(fld: Fold, paper: &mut HashSet<(usize, usize)>) {
-    match fld {
-        Fold::Left(y) => {
-            let points_bottom = paper
-                .iter()
-                .filter(|(_, yy)| *yy > y)
-                .copied()
-                .collect_vec();
-            for &(xx, yy) in &points_bottom {
-                let diff = yy - y;
-                let insert_y = y - diff;
-                paper.insert((xx, insert_y));
-            }
-
-            for p in points_bottom {
-                paper.remove(&p);
-            }
-        }
-        Fold::Up(x) => {
-            let points_right = paper
-                .iter()
-                .filter(|(xx, _)| *xx > x)
-                .copied()
-                .collect_vec();
-            for &(xx, yy) in &points_right {
-                let diff = xx - x;
-                let insert_x = x - diff;
-                paper.insert((insert_x, yy));
-            }
-
-            for p in points_right {
-                paper.remove(&p);
-            }
+    let mut points = Vec::new();
+    for (idx, line) in lines.by_ref() {
+        if line.is_empty() {
+            break;
         }
+
+        let point = line
+            .parse::<Point>()
+            .map_err(|_| ParseError::new(13, idx + 1, 1, "a dot like `6,10`"))?;
+        points.push(point);
+    }
+
+    let folds = lines
+        .map(|(idx, line)| {
+            line.parse::<Fold>()
+                .map_err(|_| ParseError::new(13, idx + 1, 1, "a fold like `fold along y=7`"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if folds.is_empty() {
+        return Err(ParseError::new(
+            13,
+            inp.lines().count() + 1,
+            1,
+            "a fold like `fold along y=7`",
+        ));
+    }
+
+    Ok(Input { points, folds })
+}
+
+// Fails when a dot is further past the fold than the paper reaches before it.
+fn fold(fld: Fold, paper: &mut HashSet<Point>) -> Option<()> {
+    let mirror = |it: Point| match fld {
+        Fold::Left(x) if it.x > x => Some(x.checked_sub(it.x - x).map(|x| Point::new(x, it.y))),
+        Fold::Up(y) if it.y > y => Some(y.checked_sub(it.y - y).map(|y| Point::new(it.x, y))),
+        _ => None,
+    };
+
+    let folded = paper
+        .iter()
+        .filter_map(|&it| mirror(it).map(|m| Some((it, m?))))
+        .collect::<Option<Vec<_>>>()?;
+
+    for (from, to) in folded {
+        paper.remove(&from);
+        paper.insert(to);
     }
+
+    trace::event!(13, "fold", fold = fld.to_string(), dots = paper.len());
+    Some(())
 }
 
 #[aoc(day13, part1)]
 pub fn part1(inp: &Input) -> Option<usize> {
-    let mut paper = inp
-        .points
-        .iter()
-        .map(|it| (it.y, it.x))
-        .collect::<HashSet<_>>();
+    let mut paper = inp.points.iter().copied().collect::<HashSet<_>>();
 
     let first_fold = inp.folds.first()?;
-    fold(*first_fold, &mut paper);
+    fold(*first_fold, &mut paper)?;
 
     Some(paper.len())
 }
 
 #[aoc(day13, part2)]
 pub fn part2(inp: &Input) -> Option<String> {
-    let mut paper = inp
-        .points
-        .iter()
-        .map(|it| (it.y, it.x))
-        .collect::<HashSet<_>>();
+    let mut paper = inp.points.iter().copied().collect::<HashSet<_>>();
 
     for fld in &inp.folds {
-        fold(*fld, &mut paper);
+        fold(*fld, &mut paper)?;
     }
 
-    let (max_x, _) = paper.iter().max_by_key(|(xx, _)| *xx)?;
-    let (_, max_y) = paper.iter().max_by_key(|(_, yy)| *yy)?;
+    let max_x = paper.iter().map(|it| it.x).max()?;
+    let max_y = paper.iter().map(|it| it.y).max()?;
 
-    let mut result = String::from("\n");
+    let sheet = Grid::from_fn(max_x + 1, max_y + 1, |(x, y)| {
+        paper.contains(&Point::new(x, y))
+    });
 
-    for x in 0..=*max_x {
-        for y in 0..=*max_y {
-            if paper.contains(&(x, y)) {
-                result.push('#');
-            } else {
-                result.push(' ');
-            }
-        }
-
-        result.push('\n');
-    }
-
-    Some(result)
+    Some(format!(
+        "\n{}\n",
+        sheet.render(|&dot| if dot { '#' } else { ' ' })
+    ))
 }
 
 #[cfg(test)]
@@ -154,11 +140,55 @@ fold along x=5";
 
     #[test]
     fn test_sample_p1() {
-        let Some(gen) = generate(TEST_DATA) else {
+        let Ok(gen) = generate(TEST_DATA) else {
             panic!("Could not parse test input")
         };
 
         let res = part1(&gen);
         assert_eq!(res, Some(17));
     }
+
+    #[test]
+    fn test_dots_beyond_the_paper() {
+        let Ok(gen) = generate("20,1\n\nfold along x=5") else {
+            panic!("Could not parse test input")
+        };
+
+        assert_eq!(part1(&gen), None);
+        assert_eq!(part2(&gen), None);
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace_folds() {
+        use aoc_utils::json::Value;
+        use aoc_utils::trace::{self, Collector};
+        use std::rc::Rc;
+
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        let collector = Rc::new(Collector::new());
+        let _ = trace::with_subscriber(collector.clone(), || part2(&gen));
+
+        let folds = collector
+            .events()
+            .iter()
+            .map(|it| (it.field("fold").cloned(), it.field("dots").cloned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            folds,
+            [
+                (
+                    Some(Value::from("fold along y=7")),
+                    Some(Value::from(17usize))
+                ),
+                (
+                    Some(Value::from("fold along x=5")),
+                    Some(Value::from(16usize))
+                ),
+            ]
+        );
+    }
 }
@@ -1,95 +1,143 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use aoc_runner_derive::{aoc, aoc_generator};
+use core::fmt;
+use core::hash::Hash;
+use core::str::FromStr;
 use itertools::Itertools;
 use itertools::MinMaxResult::MinMax;
-use parse_display::{Display, FromStr};
-use std::collections::HashMap;
 
-#[derive(Display, FromStr, Clone, Debug)]
-#[display("{from} -> {to}")]
+use aoc_utils::collections::Map;
+use aoc_utils::count::{Count, Overflow};
+use aoc_utils::parse::{first_line, ParseError};
+use aoc_utils::trace;
+
+#[derive(Clone, Debug)]
 pub struct Mapping {
     from: String,
     to: char,
 }
 
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
+// Hand-written rather than derived so that the module builds without `std`.
+impl FromStr for Mapping {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, to) = s.split_once(" -> ").ok_or(())?;
+        Ok(Self {
+            from: from.to_string(),
+            to: to.parse().map_err(drop)?,
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Input {
     template: String,
     mappings: Vec<Mapping>,
 }
 
-#[aoc_generator(day14)]
-pub fn generate(inp: &str) -> Input {
-    let template = inp.lines().next().expect("Expected template");
+#[cfg_attr(feature = "std", aoc_generator(day14))]
+pub fn generate(inp: &str) -> Result<Input, ParseError> {
+    let template = first_line(14, inp, "a polymer template")?;
+
+    if inp.lines().nth(1).is_some_and(|it| !it.is_empty()) {
+        return Err(ParseError::new(14, 2, 1, "an empty line"));
+    }
+
     let mappings = inp
         .lines()
+        .enumerate()
         .skip(2)
-        .filter_map(|it| it.parse().ok())
-        .collect_vec();
-    Input {
+        .map(|(idx, it)| {
+            it.parse()
+                .map_err(|()| ParseError::new(14, idx + 1, 1, "a rule like `CH -> B`"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Input {
         template: template.to_string(),
         mappings,
-    }
+    })
+}
+
+fn add<K: Eq + Hash + Ord>(counts: &mut Map<K, Count>, key: K, n: &Count) -> Result<(), Overflow> {
+    let count = counts.entry(key).or_default();
+    *count = count.checked_add(n)?;
+    Ok(())
 }
 
-fn run_steps(n: usize, inp: &Input) -> usize {
-    let mut pairs = inp
-        .template
-        .chars()
-        .tuple_windows::<(_, _)>()
-        .map(|(l, r)| format!("{l}{r}"))
-        .counts();
+pub(crate) fn run_steps(n: usize, inp: &Input) -> Result<Count, Overflow> {
+    let mut pairs = Map::new();
+    for (l, r) in inp.template.chars().tuple_windows::<(_, _)>() {
+        add(&mut pairs, format!("{l}{r}"), &Count::from(1))?;
+    }
 
     let mappings = inp
         .mappings
         .iter()
         .map(|it| (&it.from, it.to))
-        .collect::<HashMap<_, _>>();
+        .collect::<Map<_, _>>();
 
+    let zero = Count::zero();
     let mut next_step_pairs = pairs.clone();
-    for _ in 0..n {
-        for (k, v) in pairs.iter().filter(|(_, &v)| v > 0) {
+    for step in 1..=n {
+        for (k, v) in pairs.iter().filter(|(_, v)| **v > zero) {
             if let Some(m) = mappings.get(k) {
                 if let [l, r, ..] = k.chars().collect_vec().as_slice() {
-                    *next_step_pairs.entry(format!("{l}{m}")).or_insert(0) += *v;
-                    *next_step_pairs.entry(format!("{m}{r}")).or_insert(0) += *v;
+                    add(&mut next_step_pairs, format!("{l}{m}"), v)?;
+                    add(&mut next_step_pairs, format!("{m}{r}"), v)?;
                     if let Some(val) = next_step_pairs.get_mut(k) {
-                        *val -= v;
+                        *val = val.checked_sub(v)?;
                     }
                 }
             }
         }
 
         pairs = next_step_pairs.clone();
+        trace::event!(
+            14,
+            "step",
+            step = step,
+            pairs = pairs
+                .iter()
+                .filter(|(_, v)| **v > zero)
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Map<_, _>>(),
+        );
     }
 
-    let mut char_freqs = pairs.iter().fold(HashMap::new(), |mut acc, (k, v)| {
-        for c in k.chars() {
-            *acc.entry(c).or_insert(0) += *v;
+    // Every element but the first ends exactly one pair.
+    let mut char_freqs = Map::new();
+    for (k, v) in &pairs {
+        if let Some(c) = k.chars().nth(1) {
+            add(&mut char_freqs, c, v)?;
         }
-        acc
-    });
-
-    char_freqs.values_mut().for_each(|it| *it /= 2);
-    *char_freqs
-        .entry(inp.template.chars().next().expect("Is not empty"))
-        .or_insert(0) += 1;
-    *char_freqs
-        .entry(inp.template.chars().last().expect("Is not empty"))
-        .or_insert(0) += 1;
+    }
+    let first = inp.template.chars().next().expect("Is not empty");
+    add(&mut char_freqs, first, &Count::from(1))?;
 
     match char_freqs.values().minmax() {
-        MinMax(min, max) => max - min,
-        _ => 0,
+        MinMax(min, max) => max.checked_sub(min),
+        _ => Ok(zero),
     }
 }
 
-#[aoc(day14, part1)]
-pub fn part1(inp: &Input) -> usize {
+#[cfg_attr(feature = "std", aoc(day14, part1))]
+pub fn part1(inp: &Input) -> Result<Count, Overflow> {
     run_steps(10, inp)
 }
 
-#[aoc(day14, part2)]
-pub fn part2(inp: &Input) -> usize {
+#[cfg_attr(feature = "std", aoc(day14, part2))]
+pub fn part2(inp: &Input) -> Result<Count, Overflow> {
     run_steps(40, inp)
 }
 
@@ -118,15 +166,62 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
-        assert_eq!(res, 1_588);
+        assert_eq!(res, Ok(Count::from(1_588)));
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
-        assert_eq!(res, 2_188_189_693_529);
+        assert_eq!(res, Ok(Count::from(2_188_189_693_529)));
+    }
+
+    #[test]
+    fn test_template_with_equal_ends() {
+        let Ok(gen) = generate("NCN\n\nCC -> N") else {
+            panic!("Could not parse test input")
+        };
+
+        let res = part1(&gen);
+        assert_eq!(res, Ok(Count::from(1)));
+    }
+
+    #[test]
+    fn test_missing_template() {
+        let res = generate("");
+        assert_eq!(
+            res.err(),
+            Some(ParseError::new(14, 1, 1, "a polymer template"))
+        );
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace_pair_histogram() {
+        use aoc_utils::trace::{self, Collector};
+        use std::rc::Rc;
+
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        let collector = Rc::new(Collector::new());
+        let res = trace::with_subscriber(collector.clone(), || part1(&gen));
+        assert_eq!(res, Ok(Count::from(1_588)));
+
+        let events = collector.events();
+        assert_eq!(events.len(), 10);
+        assert_eq!(
+            events[0].to_string(),
+            r#"{"day":14,"event":"step","step":1,"pairs":{"BC":1,"CH":1,"CN":1,"HB":1,"NB":1,"NC":1}}"#
+        );
     }
 }
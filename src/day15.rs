@@ -1,55 +1,25 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
 
-#[aoc_generator(day15)]
-pub fn generate(inp: &str) -> Vec<Vec<usize>> {
-    inp.lines()
-        .map(|it| {
-            it.to_string()
-                .chars()
-                .filter_map(|it| it.to_digit(10).map(|it| it as usize))
-                .collect_vec()
-        })
-        .collect()
-}
-
-fn neighbours(cur: (usize, usize), size: usize) -> Vec<(usize, usize)> {
-    let (x, y) = cur;
-
-    let mut positions_to_check = vec![(x + 1, y), (x, y + 1)];
-    if let Some(xx) = x.checked_sub(1) {
-        positions_to_check.push((xx, y));
-    }
-    if let Some(yy) = y.checked_sub(1) {
-        positions_to_check.push((x, yy));
-    }
+use aoc_utils::grid::Grid;
+use aoc_utils::parse::ParseError;
+use aoc_utils::search;
+use aoc_utils::trace;
 
-    positions_to_check
-        .into_iter()
-        .filter(|&(x, y)| x < size && y < size)
-        .collect()
+#[aoc_generator(day15)]
+pub fn generate(inp: &str) -> Result<Grid<usize>, ParseError> {
+    Grid::parse_digits(15, inp)
 }
 
-fn find_path(inp: &[Vec<usize>]) -> usize {
-    let size = inp.len();
-
-    let (_, risk) = pathfinding::prelude::dijkstra(
-        &(0usize, 0usize),
-        |&(x, y)| {
-            neighbours((x, y), size)
-                .iter()
-                .map(|&it| (it, inp[it.0][it.1]))
-                .collect_vec()
-        },
-        |(x, y)| *x == size - 1 && *y == size - 1,
-    )
-    .expect("Expected available path");
+pub fn find_path(inp: &Grid<usize>) -> usize {
+    let target = (inp.width() - 1, inp.height() - 1);
+    let risk = search::grid_path_cost(inp, (0, 0), target).expect("Expected available path");
 
+    trace::event!(15, "path", size = (inp.width(), inp.height()), risk = risk);
     risk
 }
 
 #[aoc(day15, part1)]
-pub fn part1(inp: &[Vec<usize>]) -> usize {
+pub fn part1(inp: &Grid<usize>) -> usize {
     find_path(inp)
 }
 
@@ -62,28 +32,13 @@ const fn inc_by_step(step: usize, it: usize) -> usize {
 }
 
 #[aoc(day15, part2)]
-pub fn part2(inp: &[Vec<usize>]) -> usize {
-    let mut extended_map = inp
-        .iter()
-        .map(|it| {
-            std::iter::repeat(it)
-                .take(5)
-                .enumerate()
-                .flat_map(|(step, it)| it.iter().map(|p| inc_by_step(step, *p)).collect_vec())
-                .collect_vec()
-        })
-        .collect_vec();
+pub fn part2(inp: &Grid<usize>) -> usize {
+    let (width, height) = (inp.width(), inp.height());
 
-    let size = extended_map.len();
-    for step in 1..5 {
-        for row in 0..size {
-            let new_row = extended_map[row]
-                .iter()
-                .map(|p| inc_by_step(step, *p))
-                .collect_vec();
-            extended_map.push(new_row);
-        }
-    }
+    let extended_map = Grid::from_fn(width * 5, height * 5, |(x, y)| {
+        let step = x / width + y / height;
+        inc_by_step(step, inp[(x % width, y % height)])
+    });
 
     find_path(&extended_map)
 }
@@ -105,14 +60,20 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 40);
     }
 
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 315);
     }
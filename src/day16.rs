@@ -1,129 +1,157 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+
+use aoc_utils::parse::{first_line, ParseError};
+use aoc_utils::trace;
+
+// Every nesting level costs at least 18 bits, so real transmissions stay far below this.
+const MAX_DEPTH: usize = 64;
 
 #[aoc_generator(day16)]
-pub fn generate(inp: &str) -> Option<String> {
-    inp.lines().next().map(ToString::to_string)
+pub fn generate(inp: &str) -> Result<Packet, ParseError> {
+    let line = first_line(16, inp, "a hexadecimal transmission")?;
+
+    if let Some(pos) = line.chars().position(|c| !c.is_ascii_hexdigit()) {
+        return Err(ParseError::new(16, 1, pos + 1, "a hexadecimal digit"));
+    }
+
+    parse_hex_packet(line)
 }
 
 #[derive(Clone, Debug)]
-struct Packet {
+pub struct Packet {
     version: u64,
     type_id: u64,
     value: Option<u64>,
-    sub_packets: Vec<Packet>,
+    sub_packets: Vec<Self>,
 }
 
-fn from_binary(inp: &str) -> u64 {
-    u64::from_str_radix(inp, 2).expect("Input needs to be a binary string")
+struct Bits {
+    bits: Vec<bool>,
+    pos: usize,
 }
 
-fn parse_hex_packet(packet: &str) -> Packet {
-    let mut as_binary = packet
-        .chars()
-        .filter_map(|it| it.to_digit(16).map(|d| format!("{d:04b}")))
-        .flat_map(|it| it.chars().collect_vec())
-        .join("");
-    parse_packet(&mut as_binary)
-}
+impl Bits {
+    fn error(&self, expected: &str) -> ParseError {
+        ParseError::new(16, 1, self.pos / 4 + 1, expected)
+    }
 
-fn consume_first_n(inp: &mut String, n: usize) -> String {
-    inp.drain(..n).collect::<String>()
-}
+    fn take(&mut self, n: usize, expected: &str) -> Result<u64, ParseError> {
+        let bits = self
+            .bits
+            .get(self.pos..self.pos + n)
+            .ok_or_else(|| self.error(expected))?;
+        self.pos += n;
 
-fn parse_packet_version(input: &mut String) -> u64 {
-    let version_string = consume_first_n(input, 3);
-    from_binary(&version_string)
+        Ok(bits.iter().fold(0, |acc, &b| (acc << 1) | u64::from(b)))
+    }
 }
 
-fn parse_type_id_version(input: &mut String) -> u64 {
-    let type_id_string = consume_first_n(input, 3);
-    from_binary(&type_id_string)
-}
+fn parse_hex_packet(packet: &str) -> Result<Packet, ParseError> {
+    let bits = packet
+        .chars()
+        .filter_map(|it| it.to_digit(16))
+        .flat_map(|d| (0..4).rev().map(move |bit| d & (1 << bit) != 0))
+        .collect();
 
-fn parse_sub_packet_length(input: &mut String) -> u64 {
-    let bits_as_str = consume_first_n(input, 15);
-    from_binary(&bits_as_str)
+    parse_packet(&mut Bits { bits, pos: 0 }, 0)
 }
 
-fn parse_literal_value_packet(version: u64, type_id: u64, input: &mut String) -> Packet {
+fn parse_literal_value_packet(
+    version: u64,
+    type_id: u64,
+    input: &mut Bits,
+) -> Result<Packet, ParseError> {
     // literal value
-    let mut number = String::new();
+    let mut number = 0u64;
 
     loop {
-        let chunk = consume_first_n(input, 5);
-        number.push_str(&chunk[1..]);
+        let chunk = input.take(5, "a literal value group")?;
+        if number.leading_zeros() < 4 {
+            return Err(input.error("a literal value that fits in 64 bits"));
+        }
+        number = (number << 4) | (chunk & 0xF);
 
-        if chunk.starts_with('0') {
+        if chunk & 0x10 == 0 {
             break;
         }
     }
 
-    let val = from_binary(&number);
-
-    Packet {
+    Ok(Packet {
         version,
         type_id,
-        value: Some(val),
+        value: Some(number),
         sub_packets: Vec::new(),
-    }
+    })
 }
 
 #[allow(clippy::cast_possible_truncation)]
-fn parse_sub_packets_by_length(input: &mut String) -> Vec<Packet> {
+fn parse_sub_packets_by_length(input: &mut Bits, depth: usize) -> Result<Vec<Packet>, ParseError> {
     let mut result = Vec::new();
 
     // next 15 bits => total length of sub-packets
-    let bits = parse_sub_packet_length(input) as usize;
-    let mut sub_packets = consume_first_n(input, bits);
+    let bits = input.take(15, "the total length of the sub-packets")? as usize;
+    let end = input.pos + bits;
+    if end > input.bits.len() {
+        return Err(input.error("sub-packets within the transmission"));
+    }
 
-    while !sub_packets.is_empty() {
-        let sub_pack = parse_packet(&mut sub_packets);
+    while input.pos < end {
+        let sub_pack = parse_packet(input, depth + 1)?;
         result.push(sub_pack);
     }
 
-    result
+    if input.pos != end {
+        return Err(input.error("sub-packets matching their total length"));
+    }
+
+    Ok(result)
 }
 
-fn parse_sub_packets_by_count(input: &mut String) -> Vec<Packet> {
+fn parse_sub_packets_by_count(input: &mut Bits, depth: usize) -> Result<Vec<Packet>, ParseError> {
     let mut result = Vec::new();
 
     // next 11 bits => number of sub-packets
-    let length_as_str = consume_first_n(input, 11);
-    let num_sub_packets = from_binary(&length_as_str);
+    let num_sub_packets = input.take(11, "the number of sub-packets")?;
 
     for _ in 0..num_sub_packets {
-        let p = parse_packet(input);
+        let p = parse_packet(input, depth + 1)?;
         result.push(p);
     }
 
-    result
+    Ok(result)
 }
 
-fn parse_packet(input: &mut String) -> Packet {
-    let packet_version = parse_packet_version(input);
-    let packet_type_id = parse_type_id_version(input);
+fn parse_packet(input: &mut Bits, depth: usize) -> Result<Packet, ParseError> {
+    if depth > MAX_DEPTH {
+        return Err(input.error(&format!("at most {MAX_DEPTH} nested packets")));
+    }
+
+    let packet_version = input.take(3, "a packet version")?;
+    let packet_type_id = input.take(3, "a packet type id")?;
 
     if packet_type_id == 4 {
         return parse_literal_value_packet(packet_version, packet_type_id, input);
     }
 
     // operator packet
-    let length_type_id = consume_first_n(input, 1);
-    let sub_packets = if length_type_id == "0" {
-        parse_sub_packets_by_length(input)
-    } else if length_type_id == "1" {
-        parse_sub_packets_by_count(input)
+    let sub_packets = if input.take(1, "a length type id")? == 0 {
+        parse_sub_packets_by_length(input, depth)?
     } else {
-        panic!("Invalid length type id: {length_type_id}")
+        parse_sub_packets_by_count(input, depth)?
     };
 
-    Packet {
+    match (packet_type_id, sub_packets.len()) {
+        (0..=3, 0) => return Err(input.error("at least one sub-packet")),
+        (5..=7, n) if n != 2 => return Err(input.error("exactly two sub-packets")),
+        _ => {}
+    }
+
+    Ok(Packet {
         version: packet_version,
         type_id: packet_type_id,
         value: None,
         sub_packets,
-    }
+    })
 }
 
 fn packet_version_sum(packet: &Packet) -> u64 {
@@ -136,9 +164,8 @@ fn packet_version_sum(packet: &Packet) -> u64 {
 }
 
 #[aoc(day16, part1)]
-pub fn part1(inp: &str) -> u64 {
-    let packet = parse_hex_packet(inp);
-    packet_version_sum(&packet)
+pub fn part1(packet: &Packet) -> u64 {
+    packet_version_sum(packet)
 }
 
 fn do_calc(packet: &Packet) -> u64 {
@@ -147,6 +174,19 @@ fn do_calc(packet: &Packet) -> u64 {
         return val;
     }
 
+    let value = evaluate_operator(packet);
+    trace::event!(
+        16,
+        "operator",
+        version = packet.version,
+        type_id = packet.type_id,
+        operands = packet.sub_packets.len(),
+        value = value,
+    );
+    value
+}
+
+fn evaluate_operator(packet: &Packet) -> u64 {
     let tid = packet.type_id;
     match tid {
         0..=3 => {
@@ -187,9 +227,8 @@ fn do_calc(packet: &Packet) -> u64 {
 }
 
 #[aoc(day16, part2)]
-pub fn part2(inp: &str) -> u64 {
-    let packet = parse_hex_packet(inp);
-    do_calc(&packet)
+pub fn part2(packet: &Packet) -> u64 {
+    do_calc(packet)
 }
 
 #[cfg(test)]
@@ -198,21 +237,19 @@ mod tests {
 
     #[test]
     fn test_parse_literal_value_packet() {
-        let Some(gen) = generate("D2FE28") else {
+        let Ok(packet) = generate("D2FE28") else {
             panic!("Could not parse test input")
         };
 
-        let packet = parse_hex_packet(&gen);
         assert_eq!(packet.value, Some(2021));
     }
 
     #[test]
     fn test_parse_operator_packet_1() {
-        let Some(gen) = generate("38006F45291200") else {
+        let Ok(packet) = generate("38006F45291200") else {
             panic!("Could not parse test input")
         };
 
-        let packet = parse_hex_packet(&gen);
         assert_eq!(packet.sub_packets.len(), 2);
         assert_eq!(packet.sub_packets.first().and_then(|it| it.value), Some(10));
         assert_eq!(packet.sub_packets.get(1).and_then(|it| it.value), Some(20));
@@ -220,11 +257,10 @@ mod tests {
 
     #[test]
     fn test_parse_operator_packet_2() {
-        let Some(gen) = generate("EE00D40C823060") else {
+        let Ok(packet) = generate("EE00D40C823060") else {
             panic!("Could not parse test input")
         };
 
-        let packet = parse_hex_packet(&gen);
         assert_eq!(packet.sub_packets.len(), 3);
         assert_eq!(packet.sub_packets.first().and_then(|it| it.value), Some(1));
         assert_eq!(packet.sub_packets.get(1).and_then(|it| it.value), Some(2));
@@ -233,31 +269,31 @@ mod tests {
 
     #[test]
     fn test_sample_p1_1() {
-        let Some(gen) = generate("8A004A801A8002F478") else {
+        let Ok(packet) = generate("8A004A801A8002F478") else {
             panic!("Could not parse test input")
         };
 
-        let res = part1(&gen);
+        let res = part1(&packet);
         assert_eq!(res, 16);
     }
 
     #[test]
     fn test_sample_p1_2() {
-        let Some(gen) = generate("620080001611562C8802118E34") else {
+        let Ok(packet) = generate("620080001611562C8802118E34") else {
             panic!("Could not parse test input")
         };
 
-        let res = part1(&gen);
+        let res = part1(&packet);
         assert_eq!(res, 12);
     }
 
     #[test]
     fn test_sample_p1_3() {
-        let Some(gen) = generate("C0015000016115A2E0802F182340") else {
+        let Ok(packet) = generate("C0015000016115A2E0802F182340") else {
             panic!("Could not parse test input")
         };
 
-        let res = part1(&gen);
+        let res = part1(&packet);
         assert_eq!(res, 23);
     }
 
@@ -275,12 +311,36 @@ mod tests {
         ];
 
         for (inp, expected) in test_data {
-            let Some(gen) = generate(inp) else {
+            let Ok(packet) = generate(inp) else {
                 panic!("Could not parse test input")
             };
 
-            let res = part2(&gen);
+            let res = part2(&packet);
             assert_eq!(res, expected);
         }
     }
+
+    #[test]
+    fn test_rejects_malformed_packets() {
+        assert!(generate("38006F4529").is_err());
+        assert!(generate("C200B40A82FFFFFFFFFFFFFFFFFF").is_ok());
+
+        // 100 nested single-child operators around one literal
+        let bits = format!("{}00010000001", "000000100000000001".repeat(100));
+        let hex = bits
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| {
+                let nibble = chunk
+                    .iter()
+                    .fold(0, |acc, b| (acc << 1) | u32::from(*b == b'1'));
+                char::from_digit(nibble << (4 - chunk.len()), 16).unwrap_or('0')
+            })
+            .collect::<String>();
+
+        let Err(err) = generate(&hex) else {
+            panic!("Deeply nested packets should be rejected")
+        };
+        assert_eq!(err.expected, "at most 64 nested packets");
+    }
 }
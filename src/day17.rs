@@ -1,6 +1,16 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::iproduct;
 use parse_display::{Display, FromStr};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use aoc_utils::parse::{first_line, ParseError};
+use aoc_utils::trace;
+use aoc_utils::vector::Vec2;
+
+// Bounds of the velocity search, enough for any real puzzle input.
+const MAX_X: i64 = 1000;
+const MIN_Y: i64 = -250;
 
 #[derive(Display, FromStr, Copy, Clone, Debug)]
 #[display("target area: x={from_x}..{to_x}, y={from_y}..{to_y}")]
@@ -12,8 +22,17 @@ pub struct TargetArea {
 }
 
 impl TargetArea {
-    const fn contains(&self, x: i64, y: i64) -> bool {
-        self.from_x <= x && x <= self.to_x && self.from_y <= y && y <= self.to_y
+    const fn is_searchable(&self) -> bool {
+        0 <= self.from_x
+            && self.from_x <= self.to_x
+            && self.to_x <= MAX_X
+            && MIN_Y <= self.from_y
+            && self.from_y <= self.to_y
+            && self.to_y < 0
+    }
+
+    const fn contains(&self, pos: Vec2<i64>) -> bool {
+        self.from_x <= pos.x && pos.x <= self.to_x && self.from_y <= pos.y && pos.y <= self.to_y
     }
 
     const fn is_above(&self, y: i64) -> bool {
@@ -27,9 +46,9 @@ impl TargetArea {
             let mut probe = Probe::new(x_vel, 0);
 
             let mut hit_range = false;
-            while probe.x_vel != 0 && !hit_range {
+            while probe.vel.x != 0 && !hit_range {
                 do_step(&mut probe);
-                hit_range = self.from_x <= probe.x_pos && probe.x_pos <= self.to_x;
+                hit_range = self.from_x <= probe.pos.x && probe.pos.x <= self.to_x;
             }
 
             if hit_range {
@@ -47,44 +66,50 @@ pub struct Input {
 }
 
 struct Probe {
-    x_pos: i64,
-    y_pos: i64,
-
-    x_vel: i64,
-    y_vel: i64,
+    pos: Vec2<i64>,
+    vel: Vec2<i64>,
 }
 
 impl Probe {
     const fn new(x_vel: i64, y_vel: i64) -> Self {
         Self {
-            x_vel,
-            y_vel,
-            x_pos: 0,
-            y_pos: 0,
+            pos: Vec2::new(0, 0),
+            vel: Vec2::new(x_vel, y_vel),
         }
     }
 }
 
 #[aoc_generator(day17)]
-pub fn generate(inp: &str) -> Option<Input> {
-    let area = inp
-        .lines()
-        .next()
-        .and_then(|it| it.parse::<TargetArea>().ok())?;
+pub fn generate(inp: &str) -> Result<Input, ParseError> {
+    const EXPECTED: &str = "a target area like `target area: x=20..30, y=-10..-5`";
+
+    let area = first_line(17, inp, EXPECTED)?
+        .parse::<TargetArea>()
+        .map_err(|_| ParseError::new(17, 1, 1, EXPECTED))?;
+    if !area.is_searchable() {
+        return Err(ParseError::new(
+            17,
+            1,
+            1,
+            format!("a target area within x=0..{MAX_X}, y={MIN_Y}..-1"),
+        ));
+    }
+
     let valid_x_velocities = area.get_valid_x_velocities();
 
-    Some(Input {
+    Ok(Input {
         area,
         valid_x_velocities,
     })
 }
 
-fn do_step(probe: &mut Probe) {
-    probe.x_pos += probe.x_vel;
-    probe.y_pos += probe.y_vel;
+fn launch_velocities(valid_x_velocities: &[i64]) -> impl Iterator<Item = (i64, i64)> + '_ {
+    iproduct!(valid_x_velocities.iter().copied(), MIN_Y..=-MIN_Y)
+}
 
-    probe.x_vel -= probe.x_vel.signum();
-    probe.y_vel -= 1;
+fn do_step(probe: &mut Probe) {
+    probe.pos += probe.vel;
+    probe.vel -= Vec2::new(probe.vel.x.signum(), 1);
 }
 
 #[aoc(day17, part1)]
@@ -94,20 +119,30 @@ pub fn part1(inp: &Input) -> Option<i64> {
         valid_x_velocities,
     } = inp;
 
-    iproduct!(valid_x_velocities, -250..=250)
-        .filter_map(|(&x_vel, y_vel)| {
+    let velocities = launch_velocities(valid_x_velocities);
+    #[cfg(feature = "parallel")]
+    let velocities = velocities.par_bridge();
+
+    velocities
+        .filter_map(|(x_vel, y_vel)| {
             let mut probe = Probe::new(x_vel, y_vel);
 
             let mut cur_max_y = i64::MIN;
             loop {
                 do_step(&mut probe);
 
-                if area.is_above(probe.y_pos) {
+                if area.is_above(probe.pos.y) {
                     return None;
                 }
 
-                cur_max_y = cur_max_y.max(probe.y_pos);
-                if area.contains(probe.x_pos, probe.y_pos) {
+                cur_max_y = cur_max_y.max(probe.pos.y);
+                if area.contains(probe.pos) {
+                    trace::event!(
+                        17,
+                        "hit",
+                        velocity = Vec2::new(x_vel, y_vel),
+                        max_y = cur_max_y,
+                    );
                     return Some(cur_max_y);
                 }
             }
@@ -122,22 +157,26 @@ pub fn part2(inp: &Input) -> u64 {
         valid_x_velocities,
     } = inp;
 
-    iproduct!(valid_x_velocities, -250..=250)
-        .filter_map(|(&x_vel, y_vel)| {
+    let velocities = launch_velocities(valid_x_velocities);
+    #[cfg(feature = "parallel")]
+    let velocities = velocities.par_bridge();
+
+    velocities
+        .filter_map(|(x_vel, y_vel)| {
             let mut probe = Probe::new(x_vel, y_vel);
             loop {
                 do_step(&mut probe);
 
-                if area.is_above(probe.y_pos) {
+                if area.is_above(probe.pos.y) {
                     return None;
                 }
 
-                if area.contains(probe.x_pos, probe.y_pos) {
+                if area.contains(probe.pos) {
                     return Some(1);
                 }
             }
         })
-        .sum()
+        .sum::<u64>()
 }
 
 #[cfg(test)]
@@ -148,7 +187,7 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let Some(gen) = generate(TEST_DATA) else {
+        let Ok(gen) = generate(TEST_DATA) else {
             panic!("Could not parse test input")
         };
 
@@ -158,11 +197,22 @@ mod tests {
 
     #[test]
     fn test_sample_p2() {
-        let Some(gen) = generate(TEST_DATA) else {
+        let Ok(gen) = generate(TEST_DATA) else {
             panic!("Could not parse test input")
         };
 
         let res = part2(&gen);
         assert_eq!(res, 112);
     }
+
+    #[test]
+    fn test_rejects_unsearchable_area() {
+        for inp in [
+            "target area: x=20..30000000000, y=-10..-5",
+            "target area: x=30..20, y=-10..-5",
+            "target area: x=20..30, y=5..10",
+        ] {
+            assert!(generate(inp).is_err(), "{inp}");
+        }
+    }
 }
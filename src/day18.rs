@@ -1,12 +1,26 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use regex::Regex;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::fmt;
 use std::ops::Add;
 
+use aoc_utils::parse::ParseError;
+use aoc_utils::trace;
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum SnailfishNumber {
     Regular(u64),
-    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
+    Pair(Box<Self>, Box<Self>),
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Regular(n) => write!(f, "{n}"),
+            Self::Pair(l, r) => write!(f, "[{l},{r}]"),
+        }
+    }
 }
 
 impl Add for SnailfishNumber {
@@ -26,7 +40,7 @@ impl SnailfishNumber {
             number = next_number;
             if res.is_some() {
                 continue;
-            };
+            }
             let (next_number, res) = number.split();
             number = next_number;
             if !res {
@@ -124,82 +138,55 @@ impl SnailfishNumber {
     }
 }
 
-fn try_parse_snailfish_number(inp: &str) -> Option<SnailfishNumber> {
-    let re = Regex::new("^\\[([0-9]+),([0-9]+)]$").ok()?;
-    if re.is_match(inp) {
-        let caps = re.captures(inp)?;
-        let x = caps.get(1)?;
-        let y = caps.get(2)?;
+// Reduced numbers never nest deeper than four pairs; leave room for hand-written inputs.
+const MAX_DEPTH: usize = 16;
+// Homework only has single digits, and every larger number takes more splits to reduce.
+const MAX_REGULAR: u64 = 9;
 
-        let x = x.as_str().parse::<u64>().ok()?;
-        let y = y.as_str().parse::<u64>().ok()?;
-
-        let left = SnailfishNumber::Regular(x);
-        let right = SnailfishNumber::Regular(y);
-
-        return Some(SnailfishNumber::Pair(Box::new(left), Box::new(right)));
-    }
-
-    None
+fn consume(inp: &[u8], pos: &mut usize, expected: u8) -> Option<()> {
+    (inp.get(*pos) == Some(&expected)).then(|| *pos += 1)
 }
 
-fn parse_snailfish_number(inp: &str) -> Option<SnailfishNumber> {
-    if let Some(sfn) = try_parse_snailfish_number(inp) {
-        return Some(sfn);
+fn parse_element(inp: &[u8], pos: &mut usize, depth: usize) -> Option<SnailfishNumber> {
+    if inp.get(*pos) != Some(&b'[') {
+        let len = inp[*pos..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        let digits = std::str::from_utf8(&inp[*pos..*pos + len]).ok()?;
+        let number = digits.parse::<u64>().ok().filter(|&it| it <= MAX_REGULAR)?;
+        *pos += len;
+        return Some(SnailfishNumber::Regular(number));
     }
 
-    let contained = inp.strip_prefix('[').and_then(|it| it.strip_suffix(']'))?;
-
-    let mut split_idx = None;
-    if contained.starts_with('[') {
-        let mut bracket_stack = 0;
-        for (idx, c) in contained.chars().enumerate() {
-            if c == '[' {
-                bracket_stack += 1;
-            }
-
-            if c == ']' {
-                bracket_stack -= 1;
-                if bracket_stack == 0 {
-                    split_idx = Some(idx + 1);
-                    break;
-                }
-            }
-        }
+    if depth >= MAX_DEPTH {
+        return None;
     }
 
-    let (left, right) = split_idx.map_or_else(
-        || {
-            let comma_idx = contained
-                .chars()
-                .position(|it| it == ',')
-                .expect("Contains comma");
-            let (l, r) = contained.split_at(comma_idx);
-            (l, r.strip_prefix(',').expect("Contains comma"))
-        },
-        |split_idx| {
-            assert_eq!(contained.chars().nth(split_idx), Some(','));
-            let (l, r) = contained.split_at(split_idx);
-            (l, r.strip_prefix(',').expect("Contains comma"))
-        },
-    );
+    *pos += 1;
+    let left = parse_element(inp, pos, depth + 1)?;
+    consume(inp, pos, b',')?;
+    let right = parse_element(inp, pos, depth + 1)?;
+    consume(inp, pos, b']')?;
 
-    let try_parse_snailfish = |s: &str| {
-        s.parse::<u64>().map_or_else(
-            |_| parse_snailfish_number(s),
-            |x| Some(SnailfishNumber::Regular(x)),
-        )
-    };
+    Some(SnailfishNumber::Pair(Box::new(left), Box::new(right)))
+}
 
-    // try parsing as number
-    let x = try_parse_snailfish(left)?;
-    let y = try_parse_snailfish(right)?;
-    Some(SnailfishNumber::Pair(Box::new(x), Box::new(y)))
+fn parse_snailfish_number(inp: &str) -> Option<SnailfishNumber> {
+    let mut pos = 0;
+    let number = parse_element(inp.as_bytes(), &mut pos, 0)?;
+    (pos == inp.len() && matches!(number, SnailfishNumber::Pair(..))).then_some(number)
 }
 
 #[aoc_generator(day18)]
-pub fn generate(inp: &str) -> Vec<SnailfishNumber> {
-    inp.lines().filter_map(parse_snailfish_number).collect_vec()
+pub fn generate(inp: &str) -> Result<Vec<SnailfishNumber>, ParseError> {
+    inp.lines()
+        .enumerate()
+        .map(|(idx, it)| {
+            parse_snailfish_number(it)
+                .ok_or_else(|| ParseError::new(18, idx + 1, 1, "a snailfish number"))
+        })
+        .collect()
 }
 
 #[aoc(day18, part1)]
@@ -207,17 +194,29 @@ pub fn part1(inp: &[SnailfishNumber]) -> Option<u64> {
     Some(
         inp.iter()
             .cloned()
-            .reduce(|l, r| (l + r).reduce())?
+            .reduce(|l, r| {
+                let sum = (l + r).reduce();
+                trace::event!(
+                    18,
+                    "sum",
+                    number = sum.to_string(),
+                    magnitude = sum.magnitude()
+                );
+                sum
+            })?
             .magnitude(),
     )
 }
 
 #[aoc(day18, part2)]
 pub fn part2(inp: &[SnailfishNumber]) -> Option<u64> {
-    inp.iter()
-        .cloned()
-        .permutations(2)
-        .filter_map(|it| Some(it.into_iter().reduce(|l, r| (l + r).reduce())?.magnitude()))
+    let pairs = (0..inp.len()).cartesian_product(0..inp.len());
+    #[cfg(feature = "parallel")]
+    let pairs = pairs.par_bridge();
+
+    pairs
+        .filter(|(lhs, rhs)| lhs != rhs)
+        .map(|(lhs, rhs)| (inp[lhs].clone() + inp[rhs].clone()).reduce().magnitude())
         .max()
 }
 
@@ -282,4 +281,28 @@ mod tests {
         );
         assert_eq!(sfn, Some(expected));
     }
+
+    #[test]
+    fn test_parsing_rejects_malformed() {
+        let deep = "[".repeat(100_000);
+        for inp in [
+            "",
+            "7",
+            "[1,2",
+            "[1,2]]",
+            "[[1,2]",
+            "[1;2]",
+            "[,]",
+            "[10,1]",
+            "[18446744073709551615,1]",
+            &deep,
+        ] {
+            assert_eq!(parse_snailfish_number(inp), None, "{inp:.20}");
+        }
+
+        assert_eq!(
+            generate("[1,2]\n[1,[2]]"),
+            Err(ParseError::new(18, 2, 1, "a snailfish number"))
+        );
+    }
 }
@@ -2,88 +2,55 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use parse_display::FromStr;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::collections::HashSet;
 
-#[derive(FromStr, Hash, PartialEq, Eq, Copy, Clone)]
-#[display("{x},{y},{z}")]
-pub struct Coords {
-    x: i64,
-    y: i64,
-    z: i64,
-}
+use aoc_utils::parse::ParseError;
+use aoc_utils::trace;
+use aoc_utils::vector::Vec3;
 
-impl std::ops::Add for Coords {
-    type Output = Self;
+pub type Coords = Vec3<i64>;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
-    }
-}
+#[aoc_generator(day19)]
+pub fn generate(inp: &str) -> Result<Vec<Vec<Coords>>, ParseError> {
+    let mut result = Vec::new();
 
-impl std::ops::Sub for Coords {
-    type Output = Self;
+    for (idx, line) in inp.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
-    }
-}
+        if line.starts_with("---") {
+            result.push(Vec::new());
+            continue;
+        }
 
-impl Coords {
-    const fn new(x: i64, y: i64, z: i64) -> Self {
-        Self { x, y, z }
-    }
+        let Some(scan) = result.last_mut() else {
+            return Err(ParseError::new(19, idx + 1, 1, "a scanner header"));
+        };
 
-    const fn distance(&self, other: &Self) -> i64 {
-        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+        let coords = line
+            .parse::<Coords>()
+            .map_err(|_| ParseError::new(19, idx + 1, 1, "a beacon like `-618,-824,-621`"))?;
+        scan.push(coords);
     }
 
-    fn get_rotation(&self, num: usize) -> Self {
-        let (x, y, z) = (self.x, self.y, self.z);
-
-        match num {
-            0 => Self::new(x, y, z),
-            1 => Self::new(x, z, -y),
-            2 => Self::new(x, -y, -z),
-            3 => Self::new(x, -z, y),
-            4 => Self::new(y, x, -z),
-            5 => Self::new(y, z, x),
-            6 => Self::new(y, -x, z),
-            7 => Self::new(y, -z, -x),
-            8 => Self::new(z, x, y),
-            9 => Self::new(z, y, -x),
-            10 => Self::new(z, -x, -y),
-            11 => Self::new(z, -y, x),
-            12 => Self::new(-x, y, -z),
-            13 => Self::new(-x, z, y),
-            14 => Self::new(-x, -y, z),
-            15 => Self::new(-x, -z, -y),
-            16 => Self::new(-y, x, z),
-            17 => Self::new(-y, z, -x),
-            18 => Self::new(-y, -x, -z),
-            19 => Self::new(-y, -z, x),
-            20 => Self::new(-z, x, -y),
-            21 => Self::new(-z, y, x),
-            22 => Self::new(-z, -x, y),
-            23 => Self::new(-z, -y, -x),
-            _ => unreachable!(),
-        }
+    if result.is_empty() {
+        return Err(ParseError::new(19, 1, 1, "a scanner header"));
     }
-}
 
-#[aoc_generator(day19)]
-pub fn generate(inp: &str) -> Vec<Vec<Coords>> {
-    inp.split("\n\n")
-        .map(|s| {
-            s.lines()
-                .filter_map(|it| it.parse::<Coords>().ok())
-                .collect_vec()
-        })
-        .collect()
+    Ok(result)
 }
 
-fn try_rotation(total_scan: &mut HashSet<Coords>, scan: &[Coords], n: usize) -> Option<Coords> {
-    let nth_rotation = scan.iter().map(|&v| v.get_rotation(n)).collect_vec();
+// Returns the `n`th rotation of `scan` translated into `total_scan`'s frame, along
+// with the scanner's position, if at least 12 beacons overlap.
+fn try_rotation(
+    total_scan: &HashSet<Coords>,
+    scan: &[Coords],
+    n: usize,
+) -> Option<(Vec<Coords>, Coords)> {
+    let nth_rotation = scan.iter().map(|&v| v.rotate(n)).collect_vec();
     let distances = total_scan
         .iter()
         .cartesian_product(&nth_rotation)
@@ -97,8 +64,7 @@ fn try_rotation(total_scan: &mut HashSet<Coords>, scan: &[Coords], n: usize) ->
             .count()
             >= 12
         {
-            total_scan.extend(translated);
-            return Some(dist);
+            return Some((translated.collect(), dist));
         }
     }
 
@@ -106,7 +72,27 @@ fn try_rotation(total_scan: &mut HashSet<Coords>, scan: &[Coords], n: usize) ->
 }
 
 fn merge_scan(total_scan: &mut HashSet<Coords>, scan: &[Coords]) -> Option<Coords> {
-    (0..24).find_map(|r| try_rotation(total_scan, scan, r))
+    let rotations = 0..24;
+
+    #[cfg(not(feature = "parallel"))]
+    let found = rotations
+        .into_iter()
+        .find_map(|r| try_rotation(total_scan, scan, r));
+    #[cfg(feature = "parallel")]
+    let found = rotations
+        .into_par_iter()
+        .find_map_first(|r| try_rotation(total_scan, scan, r));
+
+    let (beacons, dist) = found?;
+    total_scan.extend(beacons);
+    trace::event!(
+        19,
+        "merged_scanner",
+        position = dist,
+        beacons = total_scan.len()
+    );
+
+    Some(dist)
 }
 
 fn split_first_scan(inp: &mut Vec<Vec<Coords>>) -> HashSet<Coords> {
@@ -149,6 +135,6 @@ pub fn part2(inp: &[Vec<Coords>]) -> Option<i64> {
     dists
         .iter()
         .tuple_combinations()
-        .map(|(lhs, rhs)| lhs.distance(rhs))
+        .map(|(lhs, rhs)| lhs.manhattan(*rhs))
         .max()
 }
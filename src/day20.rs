@@ -1,138 +1,143 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+use itertools::iproduct;
+
+use aoc_utils::grid::Grid;
+use aoc_utils::parse::{first_line, ParseError};
+use aoc_utils::trace;
 
 #[derive(Clone, Debug)]
 pub struct Input {
-    img_enhancement: String,
-    image: Vec<Vec<char>>,
+    img_enhancement: Vec<bool>,
+    image: Grid<bool>,
+}
+
+const ALGORITHM_LEN: usize = 512;
+
+const fn parse_pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
 }
 
 #[aoc_generator(day20)]
-pub fn generate(inp: &str) -> Option<Input> {
-    let img_enhancement = inp.lines().next()?.to_string();
+pub fn generate(inp: &str) -> Result<Input, ParseError> {
+    let algo = first_line(20, inp, "an image enhancement algorithm")?;
+    let img_enhancement = algo
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| parse_pixel(c).ok_or_else(|| ParseError::new(20, 1, idx + 1, "`#` or `.`")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if img_enhancement.len() != ALGORITHM_LEN {
+        return Err(ParseError::new(
+            20,
+            1,
+            img_enhancement.len().min(ALGORITHM_LEN) + 1,
+            format!("an algorithm of {ALGORITHM_LEN} characters"),
+        ));
+    }
 
-    let image = inp
-        .lines()
-        .skip(2)
-        .map(|it| it.chars().collect_vec())
-        .collect_vec();
+    let image = Grid::from_lines(
+        20,
+        inp.lines().enumerate().skip(2),
+        parse_pixel,
+        "`#` or `.`",
+    )?;
 
-    Some(Input {
+    Ok(Input {
         img_enhancement,
         image,
     })
 }
 
-fn cube_as_string(image: &[Vec<char>], x: usize, y: usize, outside: char) -> String {
-    let height = image.len();
-    let width = image[0].len();
-
-    let mut as_str = String::new();
-
-    let top_left = if x > 0 && y > 0 {
-        image[x - 1][y - 1]
-    } else {
-        outside
-    };
-
-    as_str.push(top_left);
-
-    let top = if x > 0 { image[x - 1][y] } else { outside };
-    as_str.push(top);
-
-    let top_right = if x > 0 && y + 1 < width {
-        image[x - 1][y + 1]
-    } else {
-        outside
-    };
-    as_str.push(top_right);
-
-    let left = if y > 0 { image[x][y - 1] } else { outside };
-    as_str.push(left);
-
-    let mid = image[x][y];
-    as_str.push(mid);
-
-    let right = if y + 1 < width {
-        image[x][y + 1]
-    } else {
-        outside
-    };
-    as_str.push(right);
-
-    let bot_left = if x + 1 < height && y > 0 {
-        image[x + 1][y - 1]
-    } else {
-        outside
-    };
-    as_str.push(bot_left);
-
-    let bot = if x + 1 < height {
-        image[x + 1][y]
-    } else {
-        outside
-    };
-    as_str.push(bot);
-
-    let bot_right = if x + 1 < height && y + 1 < width {
-        image[x + 1][y + 1]
-    } else {
-        outside
-    };
-    as_str.push(bot_right);
-
-    as_str
-        .chars()
-        .map(|it| match it {
-            '#' => '1',
-            '.' => '0',
-            _ => unreachable!(),
-        })
-        .join("")
+fn enhancement_index(image: &Grid<bool>, pos: (usize, usize), outside: bool) -> usize {
+    iproduct!(-1..=1, -1..=1).fold(0, |acc, (dy, dx)| {
+        let lit = image.offset(pos, (dx, dy)).map_or(outside, |it| image[it]);
+        (acc << 1) | usize::from(lit)
+    })
 }
 
-fn convert(image: &[Vec<char>], algo: &str, outside: char) -> Vec<Vec<char>> {
-    let mut image = image.to_vec();
-    image.insert(0, vec![outside; image[0].len()]);
-    image.push(vec![outside; image[0].len()]);
-    for row in &mut image {
-        row.insert(0, outside);
-        row.push(outside);
-    }
-
-    let mut output_image = Vec::new();
-    output_image.resize(image.len(), vec![outside; image[0].len()]);
+fn convert(image: &Grid<bool>, algo: &[bool], outside: bool) -> Grid<bool> {
+    let (width, height) = (image.width(), image.height());
+    let image = Grid::from_fn(width + 2, height + 2, |(x, y)| {
+        if x == 0 || y == 0 || x > width || y > height {
+            outside
+        } else {
+            image[(x - 1, y - 1)]
+        }
+    });
 
-    for x in 0..image.len() {
-        for y in 0..image[x].len() {
-            let as_str = cube_as_string(&image, x, y, outside);
-            let num = usize::from_str_radix(&as_str, 2).expect("Is binary number");
+    Grid::from_fn(image.width(), image.height(), |pos| {
+        algo[enhancement_index(&image, pos, outside)]
+    })
+}
 
-            let output_pixel = algo.chars().nth(num).expect("'num' is a valid index");
-            output_image[x][y] = output_pixel;
-        }
+pub fn enhance(inp: &Input, steps: usize) -> usize {
+    let mut outside = false;
+    let mut result = inp.image.clone();
+    for step in 1..=steps {
+        result = convert(&result, &inp.img_enhancement, outside);
+        outside = inp.img_enhancement[if outside { ALGORITHM_LEN - 1 } else { 0 }];
+        trace::event!(
+            20,
+            "enhance",
+            step = step,
+            lit = result.iter().filter(|it| **it).count(),
+            size = (result.width(), result.height()),
+        );
     }
 
-    output_image
+    result.iter().filter(|it| **it).count()
 }
 
 #[aoc(day20, part1)]
 pub fn part1(inp: &Input) -> usize {
-    let result = convert(&inp.image, &inp.img_enhancement, '.');
-    let result = convert(&result, &inp.img_enhancement, '#');
-
-    result.iter().flatten().filter(|it| **it == '#').count()
+    enhance(inp, 2)
 }
 
 #[aoc(day20, part2)]
 pub fn part2(inp: &Input) -> usize {
-    let mut outside = '.';
+    enhance(inp, 50)
+}
 
-    let mut result = convert(&inp.image, &inp.img_enhancement, outside);
-    for _ in 0..49 {
-        outside = if outside == '.' { '#' } else { '.' };
-        result = convert(&result, &inp.img_enhancement, outside);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#.\
+                             .#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..\
+                             #.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....\
+                             #.#....###..#.##......#.....#..#..#..##..#...##.######.####.####\
+                             .#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.\
+                             #.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..\
+                             #.##.#....##..#.####....##...##..#...#......#.#.......#.......##\
+                             ..####..#...#.#.#...##..#.#..###..#####........#..####......#..#\n\
+                             \n\
+                             #..#.\n\
+                             #....\n\
+                             ##..#\n\
+                             ..#..\n\
+                             ..###";
+
+    #[test]
+    fn test_sample_p1() {
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        let res = part1(&gen);
+        assert_eq!(res, 35);
     }
 
-    result.iter().flatten().filter(|it| **it == '#').count()
+    #[test]
+    fn test_sample_p2() {
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        let res = part2(&gen);
+        assert_eq!(res, 3351);
+    }
 }
@@ -1,7 +1,13 @@
+use alloc::format;
+#[cfg(feature = "std")]
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashMap;
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+use aoc_utils::collections::Map;
+use aoc_utils::count::{Count, Overflow};
+use aoc_utils::parse::{column_of, ParseError};
+use aoc_utils::trace;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 struct GameState {
     positions: [u64; 2],
     scores: [u64; 2],
@@ -12,19 +18,36 @@ const POSSIBLE_ROLL_RESULTS: [u64; 27] = [
     3, 4, 5, 4, 5, 6, 5, 6, 7, 4, 5, 6, 5, 6, 7, 6, 7, 8, 5, 6, 7, 6, 7, 8, 7, 8, 9,
 ];
 
-#[aoc_generator(day21)]
-pub fn generate(inp: &str) -> Option<[u64; 2]> {
+#[cfg_attr(feature = "std", aoc_generator(day21))]
+pub fn generate(inp: &str) -> Result<[u64; 2], ParseError> {
     let mut lines = inp.lines();
-    let mut parse_starting_pos = || {
-        lines.next().and_then(|it| {
-            let (_, end) = it.rsplit_once(' ')?;
-            end.parse::<u64>().ok()
-        })
+    let mut parse_starting_pos = |line_no: usize| {
+        let line = lines.next().unwrap_or_default();
+        let (_, end) = line.rsplit_once(' ').ok_or_else(|| {
+            ParseError::new(
+                21,
+                line_no,
+                1,
+                format!("a line like `Player {line_no} starting position: 4`"),
+            )
+        })?;
+
+        end.parse::<u64>()
+            .ok()
+            .filter(|it| (1..=10).contains(it))
+            .ok_or_else(|| {
+                ParseError::new(
+                    21,
+                    line_no,
+                    column_of(line, end),
+                    "a starting position from 1 to 10",
+                )
+            })
     };
-    let p1_pos = parse_starting_pos()?;
-    let p2_pos = parse_starting_pos()?;
+    let p1_pos = parse_starting_pos(1)?;
+    let p2_pos = parse_starting_pos(2)?;
 
-    Some([p1_pos, p2_pos])
+    Ok([p1_pos, p2_pos])
 }
 
 fn play_game(mut state: GameState) -> Option<u64> {
@@ -39,6 +62,13 @@ fn play_game(mut state: GameState) -> Option<u64> {
             state.positions[state.player_turn] -= 10;
         }
         state.scores[state.player_turn] += state.positions[state.player_turn];
+        trace::event!(
+            21,
+            "turn",
+            player = state.player_turn + 1,
+            position = state.positions[state.player_turn],
+            score = state.scores[state.player_turn],
+        );
 
         if state.scores[state.player_turn] >= 1000 {
             break;
@@ -50,12 +80,16 @@ fn play_game(mut state: GameState) -> Option<u64> {
     Some((num_rolls as u64) * state.scores.iter().min()?)
 }
 
-fn play_all_games_impl(state: GameState, cache: &mut HashMap<GameState, [u64; 2]>) -> [u64; 2] {
+fn play_all_games_impl(
+    state: GameState,
+    winning_score: u64,
+    cache: &mut Map<GameState, [Count; 2]>,
+) -> Result<[Count; 2], Overflow> {
     if let Some(cached) = cache.get(&state) {
-        return *cached;
+        return Ok(cached.clone());
     }
 
-    let mut total_score = [0; 2];
+    let mut total_score = [Count::zero(), Count::zero()];
 
     let current_player = state.player_turn;
 
@@ -70,48 +104,55 @@ fn play_all_games_impl(state: GameState, cache: &mut HashMap<GameState, [u64; 2]
 
         new_state.scores[current_player] += new_state.positions[current_player];
 
-        if new_state.scores[current_player] >= 21 {
-            total_score[current_player] += 1;
+        if new_state.scores[current_player] >= winning_score {
+            total_score[current_player] =
+                total_score[current_player].checked_add(&Count::from(1))?;
             continue;
         }
 
-        let [p1, p2] = play_all_games_impl(new_state, cache);
+        let [p1, p2] = play_all_games_impl(new_state, winning_score, cache)?;
 
-        total_score[0] += p1;
-        total_score[1] += p2;
+        total_score[0] = total_score[0].checked_add(&p1)?;
+        total_score[1] = total_score[1].checked_add(&p2)?;
     }
 
-    cache.insert(state, total_score);
-
-    total_score
-}
+    cache.insert(state, total_score.clone());
 
-fn play_all_games(state: GameState) -> u64 {
-    let mut cache = HashMap::new();
-    let [p1, p2] = play_all_games_impl(state, &mut cache);
-    p1.max(p2)
+    Ok(total_score)
 }
 
-#[aoc(day21, part1)]
-pub fn part1(inp: &[u64; 2]) -> Option<u64> {
+// Number of universes in which each player wins.
+pub(crate) fn play_all_games(
+    positions: [u64; 2],
+    winning_score: u64,
+) -> Result<[Count; 2], Overflow> {
     let state = GameState {
-        positions: *inp,
+        positions,
         scores: [0; 2],
         player_turn: 0,
     };
 
-    play_game(state)
+    let mut cache = Map::new();
+    let wins = play_all_games_impl(state, winning_score, &mut cache)?;
+    trace::event!(21, "universes", states = cache.len(), wins = wins.to_vec());
+    Ok(wins)
 }
 
-#[aoc(day21, part2)]
-pub fn part2(inp: &[u64; 2]) -> u64 {
+#[cfg_attr(feature = "std", aoc(day21, part1))]
+pub fn part1(inp: &[u64; 2]) -> Option<u64> {
     let state = GameState {
         positions: *inp,
         scores: [0; 2],
         player_turn: 0,
     };
 
-    play_all_games(state)
+    play_game(state)
+}
+
+#[cfg_attr(feature = "std", aoc(day21, part2))]
+pub fn part2(inp: &[u64; 2]) -> Result<Count, Overflow> {
+    let [p1, p2] = play_all_games(*inp, 21)?;
+    Ok(p1.max(p2))
 }
 
 #[cfg(test)]
@@ -129,6 +170,32 @@ mod tests {
     fn test_sample_p2() {
         let inp = &[4, 8];
         let res = part2(inp);
-        assert_eq!(res, 444_356_092_776_315);
+        assert_eq!(res, Ok(Count::from(444_356_092_776_315)));
+    }
+
+    #[test]
+    fn test_rejects_invalid_positions() {
+        for pos in ["0", "11", "100000000000000", "x"] {
+            let inp = format!("Player 1 starting position: 4\nPlayer 2 starting position: {pos}");
+            assert_eq!(
+                generate(&inp),
+                Err(ParseError::new(
+                    21,
+                    2,
+                    29,
+                    "a starting position from 1 to 10"
+                )),
+                "{pos}"
+            );
+        }
+        assert_eq!(
+            generate("Player 1 starting position: 4"),
+            Err(ParseError::new(
+                21,
+                2,
+                1,
+                "a line like `Player 2 starting position: 4`"
+            ))
+        );
     }
 }
@@ -1,9 +1,14 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::{iproduct, Itertools};
+use itertools::{iproduct, process_results};
 use std::collections::HashSet;
+use std::io::BufRead;
 
 use parse_display::{Display, FromStr};
 
+use aoc_utils::parse::{column_of, ParseError};
+use aoc_utils::stream::{self, StreamError};
+use aoc_utils::trace;
+
 #[derive(Display, FromStr, PartialEq, Eq, Copy, Clone, Debug)]
 #[display("x={from_x}..{to_x},y={from_y}..{to_y},z={from_z}..{to_z}")]
 pub struct Instruction {
@@ -15,44 +20,125 @@ pub struct Instruction {
     to_z: i64,
 }
 
+impl Instruction {
+    const fn is_initialization(&self) -> bool {
+        self.from_x >= -50
+            && self.to_x <= 50
+            && self.from_y >= -50
+            && self.to_y <= 50
+            && self.from_z >= -50
+            && self.to_z <= 50
+    }
+
+    const fn intersects(&self, other: &Self) -> bool {
+        self.from_x <= other.to_x
+            && other.from_x <= self.to_x
+            && self.from_y <= other.to_y
+            && other.from_y <= self.to_y
+            && self.from_z <= other.to_z
+            && other.from_z <= self.to_z
+    }
+
+    const fn volume(&self) -> i64 {
+        (self.to_x - self.from_x + 1)
+            * (self.to_y - self.from_y + 1)
+            * (self.to_z - self.from_z + 1)
+    }
+
+    // Splits `self` into at most six disjoint cuboids covering `self` without `other`.
+    fn subtract(&self, other: &Self) -> Vec<Self> {
+        if !self.intersects(other) {
+            return vec![*self];
+        }
+
+        let mut result = Vec::new();
+        let mut rest = *self;
+
+        if rest.from_x < other.from_x {
+            result.push(Self {
+                to_x: other.from_x - 1,
+                ..rest
+            });
+            rest.from_x = other.from_x;
+        }
+        if rest.to_x > other.to_x {
+            result.push(Self {
+                from_x: other.to_x + 1,
+                ..rest
+            });
+            rest.to_x = other.to_x;
+        }
+
+        if rest.from_y < other.from_y {
+            result.push(Self {
+                to_y: other.from_y - 1,
+                ..rest
+            });
+            rest.from_y = other.from_y;
+        }
+        if rest.to_y > other.to_y {
+            result.push(Self {
+                from_y: other.to_y + 1,
+                ..rest
+            });
+            rest.to_y = other.to_y;
+        }
+
+        if rest.from_z < other.from_z {
+            result.push(Self {
+                to_z: other.from_z - 1,
+                ..rest
+            });
+        }
+        if rest.to_z > other.to_z {
+            result.push(Self {
+                from_z: other.to_z + 1,
+                ..rest
+            });
+        }
+
+        result
+    }
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub struct Input {
     flag: bool,
     instr: Instruction,
 }
 
+fn parse_step(idx: usize, line: &str) -> Result<Input, ParseError> {
+    let (flag, instr) = line.split_once(' ').unwrap_or((line, ""));
+    let flag = match flag {
+        "on" => true,
+        "off" => false,
+        _ => return Err(ParseError::new(22, idx + 1, 1, "`on` or `off`")),
+    };
+
+    let instr = instr.trim().parse::<Instruction>().map_err(|_| {
+        ParseError::new(
+            22,
+            idx + 1,
+            column_of(line, instr),
+            "a cuboid like `x=10..12,y=10..12,z=10..12`",
+        )
+    })?;
+
+    Ok(Input { flag, instr })
+}
+
 #[aoc_generator(day22)]
-pub fn generate(inp: &str) -> Vec<Input> {
+pub fn generate(inp: &str) -> Result<Vec<Input>, ParseError> {
     inp.lines()
-        .filter_map(|it| {
-            let (flag, instr) = it.split_once(' ')?;
-            let flag = flag == "on";
-            let instr = instr.trim().parse::<Instruction>().ok()?;
-            Some(Input { flag, instr })
-        })
-        .collect_vec()
+        .enumerate()
+        .map(|(idx, it)| parse_step(idx, it))
+        .collect()
 }
 
-#[aoc(day22, part1)]
-pub fn part1(inp: &[Input]) -> usize {
-    let inp = inp
-        .iter()
-        .filter(|it| {
-            it.instr.from_x >= -50
-                && it.instr.to_x <= 50
-                && it.instr.from_y >= -50
-                && it.instr.to_y <= 50
-                && it.instr.from_z >= -50
-                && it.instr.to_z <= 50
-        })
-        .copied()
-        .collect_vec();
-
+fn count_initialization_cubes(steps: impl Iterator<Item = Input>) -> usize {
     let mut lights = HashSet::new();
 
-    for iter in inp {
-        let Input { flag, instr } = iter;
-
+    for Input { flag, instr } in steps.filter(|it| it.instr.is_initialization()) {
         for (x, y, z) in iproduct!(
             instr.from_x..=instr.to_x,
             instr.from_y..=instr.to_y,
@@ -69,6 +155,34 @@ pub fn part1(inp: &[Input]) -> usize {
     lights.len()
 }
 
+#[aoc(day22, part1)]
+pub fn part1(inp: &[Input]) -> usize {
+    count_initialization_cubes(inp.iter().copied())
+}
+
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<usize, StreamError> {
+    process_results(stream::parse_lines(reader, parse_step), |steps| {
+        count_initialization_cubes(steps)
+    })
+}
+
+// Keeps a list of disjoint lit cuboids instead of the compressed grid `part2` builds,
+// so memory depends on the shape of the lit region rather than the number of steps.
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<i64, StreamError> {
+    let mut lit = Vec::<Instruction>::new();
+
+    for step in stream::parse_lines(reader, parse_step) {
+        let Input { flag, instr } = step?;
+
+        lit = lit.iter().flat_map(|it| it.subtract(&instr)).collect();
+        if flag {
+            lit.push(instr);
+        }
+    }
+
+    Ok(lit.iter().map(Instruction::volume).sum())
+}
+
 #[aoc(day22, part2)]
 pub fn part2(inp: &[Input]) -> i64 {
     let mut xs = Vec::new();
@@ -94,7 +208,15 @@ pub fn part2(inp: &[Input]) -> i64 {
     let get_position_of =
         |tgt: i64, v: &[i64]| v.iter().position(|it| *it >= tgt).unwrap_or_default();
 
-    for iter in inp {
+    for (idx, iter) in inp.iter().enumerate() {
+        trace::event!(
+            22,
+            "step",
+            step = idx + 1,
+            on = iter.flag,
+            cuboid = iter.instr.to_string(),
+        );
+
         let min_x = get_position_of(iter.instr.from_x, &xs);
         let max_x = get_position_of(iter.instr.to_x + 1, &xs);
         let min_y = get_position_of(iter.instr.from_y, &ys);
@@ -147,7 +269,10 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 590_784);
     }
@@ -213,10 +338,39 @@ mod tests {
                                on x=-53470..21291,y=-120233..-33476,z=-44150..38147\n\
                                off x=-93533..-4276,y=-16170..68771,z=-104985..-24507";
 
+    #[test]
+    fn test_invalid_step() {
+        let res = generate("on x=-20..26,y=-36..17,z=-47..7\nof x=-20..33,y=-21..23,z=-26..28");
+        assert_eq!(res, Err(ParseError::new(22, 2, 1, "`on` or `off`")));
+
+        let res = generate("on x=-20..26,y=-36..17,z=-47..7\non x=-20..33,y=-21,z=-26..28");
+        assert_eq!(
+            res,
+            Err(ParseError::new(
+                22,
+                2,
+                4,
+                "a cuboid like `x=10..12,y=10..12,z=10..12`"
+            ))
+        );
+    }
+
     #[test]
     fn test_sample_p2() {
-        let gen = generate(TEST_DATA_2);
+        let Ok(gen) = generate(TEST_DATA_2) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part2(&gen);
         assert_eq!(res, 2_758_514_936_282_235);
     }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(part1_streaming(TEST_DATA.as_bytes()).ok(), Some(590_784));
+        assert_eq!(
+            part2_streaming(TEST_DATA_2.as_bytes()).ok(),
+            Some(2_758_514_936_282_235)
+        );
+    }
 }
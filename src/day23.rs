@@ -1,13 +1,240 @@
-// Solved by hand
+use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
 
-use aoc_runner_derive::aoc;
+use aoc_utils::parse::ParseError;
+use aoc_utils::search;
+use aoc_utils::trace;
+
+const HALLWAY_LEN: usize = 11;
+const ROOM_ENTRANCES: [usize; 4] = [2, 4, 6, 8];
+const ENERGY: [usize; 4] = [1, 10, 100, 1000];
+
+const UNFOLDED_ROWS: [[u8; 4]; 2] = [[3, 2, 1, 0], [3, 1, 0, 2]];
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Burrow {
+    hallway: [Option<u8>; HALLWAY_LEN],
+    rooms: [Vec<Option<u8>>; 4],
+}
+
+// `Some(None)` is an empty space, `None` an invalid character.
+#[allow(clippy::option_option)]
+const fn parse_amphipod(c: char) -> Option<Option<u8>> {
+    match c {
+        'A' => Some(Some(0)),
+        'B' => Some(Some(1)),
+        'C' => Some(Some(2)),
+        'D' => Some(Some(3)),
+        '.' => Some(None),
+        _ => None,
+    }
+}
+
+#[aoc_generator(day23)]
+pub fn generate(inp: &str) -> Result<Burrow, ParseError> {
+    const EXPECTED_HALLWAY: &str = "a hallway like `#...........#`";
+
+    let mut lines = inp.lines().skip(1);
+
+    let hallway = lines
+        .next()
+        .map(str::trim)
+        .and_then(|it| it.strip_prefix('#'))
+        .and_then(|it| it.strip_suffix('#'))
+        .and_then(|it| it.chars().map(parse_amphipod).collect::<Option<Vec<_>>>())
+        .and_then(|it| <[Option<u8>; HALLWAY_LEN]>::try_from(it).ok())
+        .ok_or_else(|| ParseError::new(23, 2, 1, EXPECTED_HALLWAY))?;
+
+    let mut rooms: [Vec<Option<u8>>; 4] = Default::default();
+    for (idx, line) in lines.enumerate() {
+        let row = line.chars().filter_map(parse_amphipod).collect_vec();
+        if row.is_empty() {
+            continue;
+        }
+
+        if row.len() != rooms.len() {
+            return Err(ParseError::new(
+                23,
+                idx + 3,
+                1,
+                "a row of four room positions like `#A#D#C#A#`",
+            ));
+        }
+
+        for (room, amphipod) in rooms.iter_mut().zip(row) {
+            room.push(amphipod);
+        }
+    }
+
+    if rooms[0].is_empty() {
+        return Err(ParseError::new(
+            23,
+            3,
+            1,
+            "a row of four room positions like `###B#C#B#D###`",
+        ));
+    }
+
+    Ok(Burrow { hallway, rooms })
+}
+
+impl Burrow {
+    fn unfold(&self) -> Self {
+        let mut result = self.clone();
+        for (idx, room) in result.rooms.iter_mut().enumerate() {
+            for (offset, row) in UNFOLDED_ROWS.iter().enumerate() {
+                room.insert(1 + offset, Some(row[idx]));
+            }
+        }
+
+        result
+    }
+
+    fn is_organized(&self) -> bool {
+        self.rooms.iter().enumerate().all(|(kind, room)| {
+            room.iter()
+                .all(|it| it.is_some_and(|amphipod| usize::from(amphipod) == kind))
+        })
+    }
+
+    fn accepts_amphipods(&self, room: usize) -> bool {
+        self.rooms[room]
+            .iter()
+            .flatten()
+            .all(|&amphipod| usize::from(amphipod) == room)
+    }
+
+    fn is_hallway_clear(&self, from: usize, to: usize) -> bool {
+        let range = if from < to {
+            from + 1..to + 1
+        } else {
+            to..from
+        };
+
+        self.hallway[range].iter().all(Option::is_none)
+    }
+
+    fn moves_into_rooms(&self) -> Vec<(Self, usize)> {
+        let mut result = Vec::new();
+
+        for (pos, amphipod) in self.hallway.iter().enumerate() {
+            let Some(amphipod) = *amphipod else {
+                continue;
+            };
+
+            let room = usize::from(amphipod);
+            let entrance = ROOM_ENTRANCES[room];
+            if !self.accepts_amphipods(room) || !self.is_hallway_clear(pos, entrance) {
+                continue;
+            }
+
+            let Some(depth) = self.rooms[room].iter().rposition(Option::is_none) else {
+                continue;
+            };
+
+            let mut next = self.clone();
+            next.hallway[pos] = None;
+            next.rooms[room][depth] = Some(amphipod);
+
+            let steps = pos.abs_diff(entrance) + depth + 1;
+            result.push((next, steps * ENERGY[room]));
+        }
+
+        result
+    }
+
+    fn moves_into_hallway(&self) -> Vec<(Self, usize)> {
+        let mut result = Vec::new();
+
+        for (room, entrance) in ROOM_ENTRANCES.iter().copied().enumerate() {
+            if self.accepts_amphipods(room) {
+                continue;
+            }
+
+            let Some(depth) = self.rooms[room].iter().position(Option::is_some) else {
+                continue;
+            };
+            let Some(amphipod) = self.rooms[room][depth] else {
+                continue;
+            };
+
+            for pos in (0..HALLWAY_LEN).filter(|it| !ROOM_ENTRANCES.contains(it)) {
+                if !self.is_hallway_clear(entrance, pos) {
+                    continue;
+                }
+
+                let mut next = self.clone();
+                next.rooms[room][depth] = None;
+                next.hallway[pos] = Some(amphipod);
+
+                let steps = pos.abs_diff(entrance) + depth + 1;
+                result.push((next, steps * ENERGY[usize::from(amphipod)]));
+            }
+        }
+
+        result
+    }
+
+    fn successors(&self) -> Vec<(Self, usize)> {
+        // moving an amphipod into its room is always part of an optimal solution
+        if let Some(next) = self.moves_into_rooms().into_iter().next() {
+            return vec![next];
+        }
+
+        self.moves_into_hallway()
+    }
+}
+
+fn organize(burrow: &Burrow) -> Option<usize> {
+    let (path, energy) = search::shortest_path(burrow, Burrow::successors, Burrow::is_organized)?;
+    trace::event!(
+        23,
+        "organized",
+        room_depth = burrow.rooms[0].len(),
+        moves = path.len() - 1,
+        energy = energy,
+    );
+
+    Some(energy)
+}
 
 #[aoc(day23, part1)]
-pub const fn part1(_: &str) -> u64 {
-    17120
+pub fn part1(inp: &Burrow) -> Option<usize> {
+    organize(inp)
 }
 
 #[aoc(day23, part2)]
-pub const fn part2(_: &str) -> u64 {
-    47234
+pub fn part2(inp: &Burrow) -> Option<usize> {
+    organize(&inp.unfold())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &str = "#############\n\
+                             #...........#\n\
+                             ###B#C#B#D###\n  \
+                               #A#D#C#A#\n  \
+                               #########";
+
+    #[test]
+    fn test_sample_p1() {
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        let res = part1(&gen);
+        assert_eq!(res, Some(12_521));
+    }
+
+    #[test]
+    fn test_sample_p2() {
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
+        let res = part2(&gen);
+        assert_eq!(res, Some(44_169));
+    }
 }
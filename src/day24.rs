@@ -1,8 +1,11 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::{Either, Itertools};
+use itertools::Either;
 use parse_display::{Display, FromStr};
 use std::collections::HashMap;
 
+use aoc_utils::parse::{parse_lines, ParseError};
+use aoc_utils::trace;
+
 #[derive(Display, FromStr, Eq, PartialEq, Hash, Copy, Clone, Debug)]
 pub enum Instruction {
     #[display("inp {0}")]
@@ -40,8 +43,8 @@ pub enum Instruction {
 }
 
 #[aoc_generator(day24)]
-pub fn generate(inp: &str) -> Vec<Instruction> {
-    inp.lines().filter_map(|it| it.parse().ok()).collect_vec()
+pub fn generate(inp: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_lines(24, inp, "an ALU instruction")
 }
 
 fn get_reg_index(dest: char) -> usize {
@@ -182,14 +185,26 @@ fn run_program(
     None
 }
 
+fn find_model_number(inp: &[Instruction], part_one: bool) -> Option<String> {
+    let mut cache = HashMap::new();
+    let result = run_program(CpuState::default(), inp, &mut cache, part_one);
+    trace::event!(
+        24,
+        "memo",
+        largest = part_one,
+        states = cache.len(),
+        dead_ends = cache.values().filter(|it| it.is_none()).count(),
+        model_number = result.clone(),
+    );
+    result
+}
+
 #[aoc(day24, part1)]
 pub fn part1(inp: &[Instruction]) -> Option<String> {
-    let result = run_program(CpuState::default(), inp, &mut HashMap::new(), true)?;
-    Some(result)
+    find_model_number(inp, true)
 }
 
 #[aoc(day24, part2)]
 pub fn part2(inp: &[Instruction]) -> Option<String> {
-    let result = run_program(CpuState::default(), inp, &mut HashMap::new(), false)?;
-    Some(result)
+    find_model_number(inp, false)
 }
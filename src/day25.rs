@@ -1,57 +1,51 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+
+use aoc_utils::grid::Grid;
+use aoc_utils::parse::ParseError;
+use aoc_utils::trace;
 
 #[aoc_generator(day25)]
-pub fn generate(inp: &str) -> Vec<Vec<char>> {
-    inp.lines().map(|it| it.chars().collect_vec()).collect_vec()
+pub fn generate(inp: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(
+        25,
+        inp,
+        |c| matches!(c, '>' | 'v' | '.').then_some(c),
+        "`>`, `v` or `.`",
+    )
 }
 
-fn simulate_step(inp: &[Vec<char>]) -> Vec<Vec<char>> {
-    let mut result = inp.to_vec();
-
-    let height = inp.len();
-    let width = inp[0].len();
+fn move_herd(inp: &Grid<char>, herd: char, direction: (isize, isize)) -> Grid<char> {
+    let mut result = inp.clone();
 
-    // move east
-    for x in 0..height {
-        for y in 0..width {
-            let cur = inp[x][y];
-            if cur == '>' {
-                let yy = if y + 1 == width { 0 } else { y + 1 };
-                if inp[x][yy] == '.' {
-                    result[x][y] = '.';
-                    result[x][yy] = cur;
-                }
-            }
-        }
-    }
-
-    let inp = result.clone();
-
-    // move south
-    for x in 0..height {
-        for y in 0..width {
-            let cur = inp[x][y];
-            if cur == 'v' {
-                let xx = if x + 1 == height { 0 } else { x + 1 };
-                if inp[xx][y] == '.' {
-                    result[x][y] = '.';
-                    result[xx][y] = cur;
-                }
-            }
+    for pos in inp.positions().filter(|&it| inp[it] == herd) {
+        let next = inp.wrapping_offset(pos, direction);
+        if inp[next] == '.' {
+            result[pos] = '.';
+            result[next] = herd;
         }
     }
 
     result
 }
 
+pub(crate) fn simulate_step(inp: &Grid<char>) -> Grid<char> {
+    let moved_east = move_herd(inp, '>', (1, 0));
+    move_herd(&moved_east, 'v', (0, 1))
+}
+
 #[aoc(day25, part1)]
-pub fn part1(inp: &[Vec<char>]) -> u64 {
-    let mut inp = inp.to_vec();
+pub fn part1(inp: &Grid<char>) -> u64 {
+    let mut inp = inp.clone();
     let mut moves = 0;
     loop {
         let res = simulate_step(&inp);
         moves += 1;
+        trace::event!(
+            25,
+            "step",
+            step = moves,
+            moved = res.iter().zip(inp.iter()).filter(|(l, r)| l != r).count() / 2,
+        );
         if res == inp {
             break;
         }
@@ -78,7 +72,10 @@ mod tests {
 
     #[test]
     fn test_sample_p1() {
-        let gen = generate(TEST_DATA);
+        let Ok(gen) = generate(TEST_DATA) else {
+            panic!("Could not parse test input")
+        };
+
         let res = part1(&gen);
         assert_eq!(res, 58);
     }
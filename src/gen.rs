@@ -0,0 +1,886 @@
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashSet};
+use std::ops::RangeInclusive;
+
+use aoc_utils::grid::Grid;
+use aoc_utils::rng::Rng;
+use aoc_utils::vector::Vec3;
+
+use crate::{day11, day25};
+
+pub fn input(day: u8, seed: u64) -> Option<String> {
+    let generate = match day {
+        1 => day01,
+        2 => day02,
+        3 => day03,
+        4 => day04,
+        5 => day05,
+        6 => day06,
+        7 => day07,
+        8 => day08,
+        9 => day09,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        13 => day13,
+        14 => day14,
+        15 => day15,
+        16 => day16,
+        17 => day17,
+        18 => day18,
+        19 => day19,
+        20 => day20,
+        21 => day21,
+        22 => day22,
+        23 => day23,
+        24 => day24,
+        25 => day25,
+        _ => return None,
+    };
+
+    Some(generate(seed))
+}
+
+fn digit_grid(
+    rng: &mut Rng,
+    width: usize,
+    height: usize,
+    digits: RangeInclusive<i64>,
+) -> Grid<usize> {
+    Grid::from_fn(width, height, |_| rng.range(digits.clone()) as usize)
+}
+
+fn render_digits(grid: &Grid<usize>) -> String {
+    grid.render(|&d| char::from(b'0' + d as u8))
+}
+
+pub fn day01(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let count = rng.between(10..=200);
+
+    let mut depth = rng.range(100..=200);
+    (0..count)
+        .map(|_| {
+            depth = (depth + rng.range(-20..=30)).max(0);
+            depth
+        })
+        .join("\n")
+}
+
+pub fn day02(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let count = rng.between(10..=200);
+
+    let mut depth = 0;
+    (0..count)
+        .map(|_| {
+            let x = rng.range(1..=9);
+            match rng.below(3) {
+                0 => format!("forward {x}"),
+                1 if depth >= x => {
+                    depth -= x;
+                    format!("up {x}")
+                }
+                _ => {
+                    depth += x;
+                    format!("down {x}")
+                }
+            }
+        })
+        .join("\n")
+}
+
+// Mirrors `day03::find_rating_value`, which only answers when exactly one number remains.
+fn has_rating(numbers: &[u32], width: usize, most_common: bool) -> bool {
+    let mut list = numbers.to_vec();
+    for idx in (0..width).rev() {
+        if list.len() == 1 {
+            break;
+        }
+
+        let ones = list.iter().filter(|&&it| it >> idx & 1 == 1).count();
+        let zeros = list.len() - ones;
+        let bit = if most_common {
+            zeros <= ones
+        } else {
+            zeros > ones
+        };
+        list.retain(|&it| (it >> idx & 1 == 1) == bit);
+    }
+
+    list.len() == 1
+}
+
+pub fn day03(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let width = rng.between(5..=12);
+
+    let mut all = (0..1u32 << width).collect_vec();
+    loop {
+        rng.shuffle(&mut all);
+        let count = rng.between(2 * width..=all.len() / 2);
+        let numbers = &all[..count];
+
+        if has_rating(numbers, width, true) && has_rating(numbers, width, false) {
+            return numbers.iter().map(|it| format!("{it:0width$b}")).join("\n");
+        }
+    }
+}
+
+pub fn day04(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+
+    let mut numbers = (0..100).collect_vec();
+    rng.shuffle(&mut numbers);
+    let drawn = numbers.iter().join(",");
+
+    let boards = (0..rng.between(3..=20))
+        .map(|_| {
+            rng.shuffle(&mut numbers);
+            numbers[..25]
+                .chunks(5)
+                .map(|row| row.iter().map(|it| format!("{it:>2}")).join(" "))
+                .join("\n")
+        })
+        .join("\n\n");
+
+    format!("{drawn}\n\n{boards}")
+}
+
+pub fn day05(seed: u64) -> String {
+    const MAX: i64 = 99;
+
+    let mut rng = Rng::new(seed);
+    let count = rng.between(10..=300);
+
+    (0..count)
+        .map(|_| loop {
+            let (x1, y1) = (rng.range(0..=MAX), rng.range(0..=MAX));
+            let (x2, y2) = match rng.below(3) {
+                0 => (rng.range(0..=MAX), y1),
+                1 => (x1, rng.range(0..=MAX)),
+                _ => {
+                    let (dx, dy) = (*rng.choose(&[-1, 1]), *rng.choose(&[-1, 1]));
+                    let max_x = if dx > 0 { MAX - x1 } else { x1 };
+                    let max_y = if dy > 0 { MAX - y1 } else { y1 };
+                    let len = rng.range(0..=max_x.min(max_y));
+                    (x1 + dx * len, y1 + dy * len)
+                }
+            };
+
+            if (x1, y1) != (x2, y2) {
+                break format!("{x1},{y1} -> {x2},{y2}");
+            }
+        })
+        .join("\n")
+}
+
+pub fn day06(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let count = rng.between(50..=300);
+    (0..count).map(|_| rng.range(1..=5)).join(",")
+}
+
+pub fn day07(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let count = rng.between(10..=500);
+    (0..count).map(|_| rng.range(0..=1000)).join(",")
+}
+
+pub fn day08(seed: u64) -> String {
+    const DIGITS: [&str; 10] = [
+        "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+    ];
+
+    let mut rng = Rng::new(seed);
+    let count = rng.between(10..=200);
+
+    (0..count)
+        .map(|_| {
+            let mut wiring = "abcdefg".chars().collect_vec();
+            rng.shuffle(&mut wiring);
+            let shown = (0..4).map(|_| rng.below(DIGITS.len())).collect_vec();
+
+            let mut scramble = |digit: &str| {
+                let mut segments = digit
+                    .bytes()
+                    .map(|it| wiring[usize::from(it - b'a')])
+                    .collect_vec();
+                rng.shuffle(&mut segments);
+                segments.into_iter().collect::<String>()
+            };
+
+            let mut patterns = DIGITS.iter().map(|it| scramble(it)).collect_vec();
+            let output = shown.iter().map(|&it| scramble(DIGITS[it])).join(" ");
+            rng.shuffle(&mut patterns);
+
+            format!("{} | {output}", patterns.join(" "))
+        })
+        .join("\n")
+}
+
+pub fn day09(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let (width, height) = (rng.between(5..=40), rng.between(5..=40));
+
+    let grid = Grid::from_fn(width, height, |_| {
+        if rng.chance(25) {
+            9
+        } else {
+            rng.range(0..=8) as usize
+        }
+    });
+
+    render_digits(&grid)
+}
+
+fn write_chunk(rng: &mut Rng, depth: usize, out: &mut String) {
+    let (open, close) = *rng.choose(&[('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')]);
+
+    out.push(open);
+    while depth < 8 && rng.chance(45) {
+        write_chunk(rng, depth + 1, out);
+    }
+    out.push(close);
+}
+
+fn open_chunks_after(line: &[char]) -> usize {
+    line.iter().fold(0_isize, |acc, c| {
+        if "([{<".contains(*c) {
+            acc + 1
+        } else {
+            acc - 1
+        }
+    }) as usize
+}
+
+pub fn day10(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let count = rng.between(10..=100);
+
+    // Part 2 takes the middle completion score, so there has to be an odd number of
+    // incomplete lines.
+    let mut incomplete = (0..count).map(|_| rng.chance(50)).collect_vec();
+    if incomplete.iter().filter(|&&it| it).count() % 2 == 0 {
+        incomplete[0] = !incomplete[0];
+    }
+
+    incomplete
+        .into_iter()
+        .map(|incomplete| {
+            let mut line = String::new();
+            while line.len() < 20 || rng.chance(30) {
+                write_chunk(&mut rng, 0, &mut line);
+            }
+            let mut line = line.chars().collect_vec();
+
+            if incomplete {
+                let cuts = (1..line.len())
+                    .filter(|&it| open_chunks_after(&line[..it]) > 0)
+                    .collect_vec();
+                line.truncate(*rng.choose(&cuts));
+            } else {
+                let closing = line
+                    .iter()
+                    .positions(|it| ")]}>".contains(*it))
+                    .collect_vec();
+                let pos = *rng.choose(&closing);
+                let wrong = ")]}>".chars().filter(|it| *it != line[pos]).collect_vec();
+                line[pos] = *rng.choose(&wrong);
+            }
+
+            line.into_iter().collect::<String>()
+        })
+        .join("\n")
+}
+
+pub fn day11(seed: u64) -> String {
+    const MAX_STEPS: usize = 5000;
+
+    let mut rng = Rng::new(seed);
+    loop {
+        let grid = digit_grid(&mut rng, 10, 10, 0..=9);
+
+        // Part 2 only terminates once all octopuses flash at the same time.
+        let mut next = grid.clone();
+        if (0..MAX_STEPS).any(|_| {
+            day11::do_step(&mut next);
+            day11::all_flashed(&next)
+        }) {
+            return render_digits(&grid);
+        }
+    }
+}
+
+fn cave_name(rng: &mut Rng, taken: &mut HashSet<String>, upper: bool) -> String {
+    loop {
+        let name = (0..2)
+            .map(|_| {
+                let c = char::from(b'a' + rng.below(26) as u8);
+                if upper {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect::<String>();
+
+        if !matches!(name.as_str(), "start" | "end") && taken.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+pub fn day12(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut taken = HashSet::new();
+
+    let (small, big) = (rng.between(3..=5), rng.between(1..=2));
+    let mut caves = (0..small + big)
+        .map(|idx| cave_name(&mut rng, &mut taken, idx >= small))
+        .collect_vec();
+    rng.shuffle(&mut caves);
+
+    let mut nodes = vec![String::from("start")];
+    nodes.extend(caves);
+    nodes.push(String::from("end"));
+
+    // Two connected big caves would allow infinitely many paths.
+    let is_big = |name: &str| name.chars().all(|c| c.is_ascii_uppercase());
+    let mut edges = BTreeSet::new();
+    let try_connect = |from: usize, to: usize, edges: &mut BTreeSet<(usize, usize)>| {
+        let (from, to) = (from.min(to), from.max(to));
+        let allowed = from != to && !(is_big(&nodes[from]) && is_big(&nodes[to]));
+        allowed && edges.insert((from, to))
+    };
+
+    // Connect every cave to an earlier one, so `end` is reachable from `start`.
+    for to in 1..nodes.len() {
+        while !try_connect(rng.below(to), to, &mut edges) {}
+    }
+
+    for _ in 0..rng.between(1..=4) {
+        let (from, to) = (rng.below(nodes.len()), rng.below(nodes.len()));
+        try_connect(from, to, &mut edges);
+    }
+
+    let mut edges = edges.into_iter().collect_vec();
+    rng.shuffle(&mut edges);
+
+    edges
+        .into_iter()
+        .map(|(from, to)| format!("{}-{}", nodes[from], nodes[to]))
+        .join("\n")
+}
+
+pub fn day13(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+
+    // Unfold from the final sheet, so that every fold is along the middle of the paper.
+    let (mut width, mut height) = (rng.between(3..=40), rng.between(3..=8));
+    let mut folds = Vec::new();
+    for _ in 0..rng.between(2..=6) {
+        if rng.chance(50) {
+            folds.push(('x', width));
+            width = 2 * width + 1;
+        } else {
+            folds.push(('y', height));
+            height = 2 * height + 1;
+        }
+    }
+    folds.reverse();
+
+    // A dot must never end up on a fold line, or the next fold would mirror it out of
+    // the paper.
+    let on_fold = |mut x: usize, mut y: usize| {
+        folds.iter().any(|&(axis, pos)| {
+            let coord = if axis == 'x' { &mut x } else { &mut y };
+            if *coord > pos {
+                *coord = 2 * pos - *coord;
+            }
+            *coord == pos
+        })
+    };
+
+    let mut dots = BTreeSet::from([(0, 0)]);
+    for _ in 0..rng.between(1..=(width * height / 8).max(1)) {
+        let (x, y) = (rng.below(width), rng.below(height));
+        if !on_fold(x, y) {
+            dots.insert((x, y));
+        }
+    }
+
+    let mut dots = dots.into_iter().collect_vec();
+    rng.shuffle(&mut dots);
+
+    format!(
+        "{}\n\n{}",
+        dots.iter().map(|(x, y)| format!("{x},{y}")).join("\n"),
+        folds
+            .iter()
+            .map(|(axis, pos)| format!("fold along {axis}={pos}"))
+            .join("\n")
+    )
+}
+
+pub fn day14(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+
+    let mut letters = ('A'..='Z').collect_vec();
+    rng.shuffle(&mut letters);
+    letters.truncate(rng.between(3..=8));
+
+    let template = (0..rng.between(4..=20))
+        .map(|_| *rng.choose(&letters))
+        .collect::<String>();
+
+    let rules = letters
+        .iter()
+        .cartesian_product(&letters)
+        .map(|(a, b)| format!("{a}{b} -> {}", rng.choose(&letters)))
+        .join("\n");
+
+    format!("{template}\n\n{rules}")
+}
+
+pub fn day15(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let (width, height) = (rng.between(5..=40), rng.between(5..=40));
+    render_digits(&digit_grid(&mut rng, width, height, 1..=9))
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u64, len: usize) {
+    bits.extend((0..len).rev().map(|idx| value >> idx & 1 == 1));
+}
+
+fn write_literal(rng: &mut Rng, bits: &mut Vec<bool>) {
+    push_bits(bits, rng.next_u64() % 8, 3);
+    push_bits(bits, 4, 3);
+
+    let groups = rng.between(1..=3);
+    let value = rng.next_u64() % (1 << (4 * groups));
+    for group in (0..groups).rev() {
+        bits.push(group != 0);
+        push_bits(bits, value >> (4 * group) & 0xF, 4);
+    }
+}
+
+fn write_packet(rng: &mut Rng, depth: usize, bits: &mut Vec<bool>) {
+    if depth >= 3 || rng.chance(30) {
+        write_literal(rng, bits);
+        return;
+    }
+
+    let type_id = *rng.choose(&[0, 1, 2, 3, 5, 6, 7]);
+    let mut sub_packets = Vec::new();
+    let count = match type_id {
+        5..=7 => 2,
+        _ => rng.between(1..=3),
+    };
+    for _ in 0..count {
+        // Products only take literals, so the result stays well within a u64.
+        if type_id == 1 {
+            write_literal(rng, &mut sub_packets);
+        } else {
+            write_packet(rng, depth + 1, &mut sub_packets);
+        }
+    }
+
+    push_bits(bits, rng.next_u64() % 8, 3);
+    push_bits(bits, type_id, 3);
+    if rng.chance(50) {
+        bits.push(false);
+        push_bits(bits, sub_packets.len() as u64, 15);
+    } else {
+        bits.push(true);
+        push_bits(bits, count as u64, 11);
+    }
+    bits.extend(sub_packets);
+}
+
+pub fn day16(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+
+    let mut bits = Vec::new();
+    write_packet(&mut rng, 0, &mut bits);
+    bits.resize(bits.len().next_multiple_of(8), false);
+
+    bits.chunks(4)
+        .map(|nibble| {
+            let value = nibble.iter().fold(0, |acc, &bit| acc << 1 | u32::from(bit));
+            char::from_digit(value, 16)
+                .expect("nibble is a hex digit")
+                .to_ascii_uppercase()
+        })
+        .collect()
+}
+
+pub fn day17(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+
+    let from_x = rng.range(10..=150);
+    let to_x = from_x + rng.range(5..=30);
+    let to_y = -rng.range(1..=50);
+    let from_y = to_y - rng.range(5..=50);
+
+    format!("target area: x={from_x}..{to_x}, y={from_y}..{to_y}")
+}
+
+fn write_snailfish_number(rng: &mut Rng, depth: usize, out: &mut String) {
+    if depth == 0 || (depth < 4 && rng.chance(50)) {
+        out.push('[');
+        write_snailfish_number(rng, depth + 1, out);
+        out.push(',');
+        write_snailfish_number(rng, depth + 1, out);
+        out.push(']');
+    } else {
+        out.push(char::from(b'0' + rng.below(10) as u8));
+    }
+}
+
+pub fn day18(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let count = rng.between(2..=20);
+
+    (0..count)
+        .map(|_| {
+            let mut number = String::new();
+            write_snailfish_number(&mut rng, 0, &mut number);
+            number
+        })
+        .join("\n")
+}
+
+pub fn day19(seed: u64) -> String {
+    const RANGE: i64 = 1000;
+
+    let mut rng = Rng::new(seed);
+    let count = rng.range(2..=4);
+
+    // Scanners form a chain along the x axis, each overlapping only its neighbours.
+    let scanners = (0..count)
+        .map(|idx| {
+            let jitter = rng.range(-100..=100);
+            Vec3::new(
+                idx * 1100 + jitter,
+                rng.range(-100..=100),
+                rng.range(-100..=100),
+            )
+        })
+        .collect_vec();
+
+    let mut random_beacon = |min: Vec3<i64>, max: Vec3<i64>| {
+        Vec3::new(
+            rng.range(min.x..=max.x),
+            rng.range(min.y..=max.y),
+            rng.range(min.z..=max.z),
+        )
+    };
+    let reach = Vec3::new(RANGE, RANGE, RANGE);
+
+    let mut beacons = BTreeSet::new();
+    for &scanner in &scanners {
+        for _ in 0..5 {
+            beacons.insert(random_beacon(scanner - reach, scanner + reach));
+        }
+    }
+    for (&lhs, &rhs) in scanners.iter().tuple_windows() {
+        let min = Vec3::new(
+            rhs.x - RANGE,
+            lhs.y.max(rhs.y) - RANGE,
+            lhs.z.max(rhs.z) - RANGE,
+        );
+        let max = Vec3::new(
+            lhs.x + RANGE,
+            lhs.y.min(rhs.y) + RANGE,
+            lhs.z.min(rhs.z) + RANGE,
+        );
+        let shared = beacons.len() + 12;
+        while beacons.len() < shared {
+            beacons.insert(random_beacon(min, max));
+        }
+    }
+
+    scanners
+        .iter()
+        .enumerate()
+        .map(|(idx, &scanner)| {
+            let rotation = rng.below(24);
+            let mut report = beacons
+                .iter()
+                .map(|&it| it - scanner)
+                .filter(|it| it.x.abs() <= RANGE && it.y.abs() <= RANGE && it.z.abs() <= RANGE)
+                .map(|it| it.rotate(rotation))
+                .collect_vec();
+            rng.shuffle(&mut report);
+
+            format!("--- scanner {idx} ---\n{}", report.iter().join("\n"))
+        })
+        .join("\n\n")
+}
+
+pub fn day20(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+
+    let mut algorithm = (0..512).map(|_| rng.chance(50)).collect_vec();
+    // An infinite image must not stay lit forever once it turns on.
+    if algorithm[0] {
+        algorithm[511] = false;
+    }
+
+    let (width, height) = (rng.between(5..=30), rng.between(5..=30));
+    let image = Grid::from_fn(width, height, |_| rng.chance(50));
+
+    let pixel = |&lit: &bool| if lit { '#' } else { '.' };
+    format!(
+        "{}\n\n{}",
+        algorithm.iter().map(pixel).collect::<String>(),
+        image.render(pixel)
+    )
+}
+
+pub fn day21(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    format!(
+        "Player 1 starting position: {}\nPlayer 2 starting position: {}",
+        rng.range(1..=10),
+        rng.range(1..=10)
+    )
+}
+
+pub fn day22(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let count = rng.between(10..=40);
+
+    (0..count)
+        .map(|idx| {
+            let flag = if idx == 0 || rng.chance(70) {
+                "on"
+            } else {
+                "off"
+            };
+
+            let mut range = || {
+                if rng.chance(70) {
+                    let from = rng.range(-50..=50);
+                    (from, (from + rng.range(0..=30)).min(50))
+                } else {
+                    let from = rng.range(-100_000..=100_000);
+                    (from, from + rng.range(1000..=40_000))
+                }
+            };
+
+            let (x, y, z) = (range(), range(), range());
+            format!(
+                "{flag} x={}..{},y={}..{},z={}..{}",
+                x.0, x.1, y.0, y.1, z.0, z.1
+            )
+        })
+        .join("\n")
+}
+
+pub fn day23(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+
+    let mut amphipods = "AABBCCDD".chars().collect_vec();
+    rng.shuffle(&mut amphipods);
+
+    format!(
+        "#############\n#...........#\n###{}###\n  #{}#\n  #########",
+        amphipods[..4].iter().join("#"),
+        amphipods[4..].iter().join("#")
+    )
+}
+
+fn monad_block(div: i64, check: i64, offset: i64) -> String {
+    format!(
+        "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {div}\nadd x {check}\neql x w\neql x 0\n\
+         mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {offset}\n\
+         mul y x\nadd z y"
+    )
+}
+
+pub fn day24(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let pairs = rng.between(1..=3);
+
+    // Every block either pushes a digit onto `z` or pops one, and each pop has to match
+    // its push so `z` can end at zero. Pairing digits that differ by at most 8 keeps
+    // the program solvable.
+    let mut blocks = Vec::new();
+    let mut open = Vec::new();
+    let mut pushes_left = pairs;
+    while pushes_left > 0 || !open.is_empty() {
+        if pushes_left > 0 && (open.is_empty() || rng.chance(50)) {
+            let offset = rng.range(1..=15);
+            blocks.push(monad_block(1, rng.range(10..=16), offset));
+            open.push(offset);
+            pushes_left -= 1;
+        } else {
+            let offset = open.pop().expect("a push is open");
+            let check = rng.range(-8..=8) - offset;
+            blocks.push(monad_block(26, check, rng.range(1..=15)));
+        }
+    }
+
+    blocks.join("\n")
+}
+
+pub fn day25(seed: u64) -> String {
+    const MAX_STEPS: usize = 5000;
+
+    let mut rng = Rng::new(seed);
+    loop {
+        let (width, height) = (rng.between(5..=30), rng.between(5..=30));
+        let grid = Grid::from_fn(width, height, |_| *rng.choose(&['>', 'v', '.']));
+
+        // Sea cucumbers can keep moving forever, so only keep grids that come to a halt.
+        let mut cur = grid.clone();
+        for _ in 0..MAX_STEPS {
+            let next = day25::simulate_step(&cur);
+            if next == cur {
+                return grid.render(|&c| c);
+            }
+            cur = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reference;
+    use crate::solver::{self, Answer, IntoAnswer, YEAR};
+    use crate::{day01, day02, day03, day22};
+    use aoc_utils::solver::Unanswered;
+
+    #[test]
+    fn test_inputs_are_reproducible() {
+        assert_eq!(day22(7), day22(7));
+        assert_ne!(day22(7), day22(8));
+    }
+
+    // What a second implementation answers for a generated input, for the parts
+    // that have one quick enough to run on it.
+    fn second_answer(day: u8, part: u8, inp: &str) -> Option<Result<Answer, Unanswered>> {
+        let reader = inp.as_bytes();
+        Some(match (day, part) {
+            (1, 1) => day01::part1_streaming(reader).ok().into_answer(),
+            (1, 2) => day01::part2_streaming(reader).ok().into_answer(),
+            (2, 1) => day02::part1_streaming(reader).ok().into_answer(),
+            (2, 2) => day02::part2_streaming(reader).ok().into_answer(),
+            (3, 1) => day03::part1_streaming(reader).ok().into_answer(),
+            (6, 1) => reference::day06(inp, 80).into_answer(),
+            (14, 1) => reference::day14(inp, 10).into_answer(),
+            (20, 1) => reference::day20(inp, 2).into_answer(),
+            (22, 2) => day22::part2_streaming(reader).ok().into_answer(),
+            _ => return None,
+        })
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for seed in 0..3 {
+            for puzzle in solver::puzzles() {
+                let Some(inp) = input(puzzle.day, seed) else {
+                    panic!("No generator for day {}", puzzle.day)
+                };
+
+                let res = solver::solve(YEAR, puzzle.day, puzzle.part, &inp);
+                assert!(
+                    res.is_ok(),
+                    "day {}, part {}, seed {seed}: {res:?}\n{inp}",
+                    puzzle.day,
+                    puzzle.part
+                );
+
+                if let Some(second) = second_answer(puzzle.day, puzzle.part, &inp) {
+                    assert_eq!(
+                        res.ok(),
+                        second.ok(),
+                        "day {}, part {}, seed {seed}\n{inp}",
+                        puzzle.day,
+                        puzzle.part
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_windows_inputs_solve_the_same() {
+        for puzzle in solver::puzzles() {
+            let Some(inp) = input(puzzle.day, 0) else {
+                panic!("No generator for day {}", puzzle.day)
+            };
+            let windows = inp.lines().map(|it| format!("{it} \r\n")).join("");
+
+            let expected = solver::solve(YEAR, puzzle.day, puzzle.part, &inp);
+            assert!(expected.is_ok());
+            for hostile in [format!("\u{feff}{inp}"), windows] {
+                assert_eq!(
+                    solver::solve(YEAR, puzzle.day, puzzle.part, &hostile),
+                    expected,
+                    "day {}, part {}",
+                    puzzle.day,
+                    puzzle.part
+                );
+            }
+        }
+    }
+
+    fn mutate(rng: &mut Rng, inp: &str) -> String {
+        const HOSTILE: &[&str] = &[
+            "",
+            "\n",
+            "\n\n",
+            ",",
+            "-",
+            "[",
+            "]",
+            "[[[[",
+            "9",
+            "99999999999999999999",
+            "#",
+            ".",
+            "x=",
+            "->",
+            " ",
+            "F",
+            "\u{e9}",
+        ];
+
+        let mut chars = inp.chars().collect_vec();
+        for _ in 0..rng.between(1..=4) {
+            let at = rng.below(chars.len() + 1);
+            match rng.below(4) {
+                0 => chars.truncate(at),
+                1 if at < chars.len() => {
+                    chars.remove(at);
+                }
+                _ => {
+                    let token = rng.choose(HOSTILE);
+                    chars.splice(at..at, token.chars());
+                }
+            }
+        }
+
+        chars.into_iter().collect()
+    }
+
+    #[test]
+    fn test_mutated_inputs_do_not_panic() {
+        let mut rng = Rng::new(0);
+        for puzzle in solver::puzzles().iter().filter(|it| it.part == 1) {
+            for seed in 0..2 {
+                let Some(inp) = input(puzzle.day, seed) else {
+                    panic!("No generator for day {}", puzzle.day)
+                };
+
+                for _ in 0..200 {
+                    let _ = puzzle.parse(&mutate(&mut rng, &inp));
+                }
+            }
+        }
+    }
+}
@@ -1,36 +1,81 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(unsafe_code)]
 #![warn(nonstandard_style, rust_2018_idioms)]
 #![allow(
     clippy::implicit_hasher,
     clippy::similar_names,
     clippy::cast_possible_truncation,
-    clippy::cast_sign_loss
+    clippy::cast_sign_loss,
+    clippy::must_use_candidate,
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc
 )]
 
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
+extern crate alloc;
 
+#[cfg(feature = "std")]
+pub mod day01;
+#[cfg(feature = "std")]
+pub mod day02;
+#[cfg(feature = "std")]
+pub mod day03;
+#[cfg(feature = "std")]
+pub mod day04;
+#[cfg(feature = "std")]
+pub mod day05;
+pub mod day06;
+pub mod day07;
+#[cfg(feature = "std")]
+pub mod day08;
+#[cfg(feature = "std")]
+pub mod day09;
+#[cfg(feature = "std")]
+pub mod day10;
+#[cfg(feature = "std")]
+pub mod day11;
+#[cfg(feature = "std")]
+pub mod day12;
+#[cfg(feature = "std")]
+pub mod day13;
+pub mod day14;
+#[cfg(feature = "std")]
+pub mod day15;
+#[cfg(feature = "std")]
+pub mod day16;
+#[cfg(feature = "std")]
+pub mod day17;
+#[cfg(feature = "std")]
+pub mod day18;
+#[cfg(feature = "std")]
+pub mod day19;
+#[cfg(feature = "std")]
+pub mod day20;
+pub mod day21;
+#[cfg(feature = "std")]
+pub mod day22;
+#[cfg(feature = "std")]
+pub mod day23;
+#[cfg(feature = "std")]
+pub mod day24;
+#[cfg(feature = "std")]
+pub mod day25;
+
+#[cfg(feature = "std")]
+pub mod gen;
+#[cfg(all(test, feature = "std"))]
+mod reference;
+#[cfg(feature = "std")]
+pub mod solver;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "std")]
+pub use aoc_utils::{cache, stream};
+pub use aoc_utils::{grid, json, parse, trace, vector};
+
+pub use parse::ParseError;
+#[cfg(feature = "std")]
+pub use solver::{solve, Answer, Error, Puzzle};
+
+#[cfg(feature = "std")]
 aoc_runner_derive::aoc_lib! { year = 2021 }
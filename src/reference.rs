@@ -0,0 +1,268 @@
+// Brute-force versions of the days whose fast solutions are hard to verify by
+// eye. They parse their own input and simulate the puzzle literally, so they
+// only scale to small inputs.
+use itertools::{iproduct, Itertools};
+use std::collections::HashSet;
+
+// Every fish is kept as its own timer.
+pub fn day06(inp: &str, num_days: u16) -> usize {
+    let mut fish = inp
+        .trim()
+        .split(',')
+        .map(|it| it.parse::<u8>().expect("Valid timer"))
+        .collect_vec();
+
+    for _ in 0..num_days {
+        let mut born = 0;
+        for timer in &mut fish {
+            if *timer == 0 {
+                *timer = 6;
+                born += 1;
+            } else {
+                *timer -= 1;
+            }
+        }
+        fish.extend(std::iter::repeat_n(8, born));
+    }
+
+    fish.len()
+}
+
+// The polymer is expanded as a string.
+pub fn day14(inp: &str, steps: usize) -> usize {
+    let mut lines = inp.lines();
+    let mut polymer = lines.next().expect("Has a template").chars().collect_vec();
+    let rules = lines
+        .filter_map(|it| it.split_once(" -> "))
+        .map(|(pair, insert)| (pair.to_string(), insert.chars().next().expect("Has insert")))
+        .collect_vec();
+
+    for _ in 0..steps {
+        let mut next = vec![polymer[0]];
+        for (&l, &r) in polymer.iter().tuple_windows() {
+            let pair = format!("{l}{r}");
+            if let Some((_, insert)) = rules.iter().find(|(it, _)| *it == pair) {
+                next.push(*insert);
+            }
+            next.push(r);
+        }
+        polymer = next;
+    }
+
+    let counts = polymer.iter().counts();
+    counts.values().max().unwrap_or(&0) - counts.values().min().unwrap_or(&0)
+}
+
+// The image is kept on a grid with a dark margin wide enough that the wrong
+// pixels spreading in from its edges never reach the ones that are counted.
+pub fn day20(inp: &str, steps: usize) -> usize {
+    let mut lines = inp.lines();
+    let algo = lines
+        .next()
+        .expect("Has an algorithm")
+        .chars()
+        .map(|c| c == '#')
+        .collect_vec();
+    let image = lines
+        .skip(1)
+        .map(|line| line.chars().map(|c| c == '#').collect_vec())
+        .collect_vec();
+
+    let margin = 2 * steps + 1;
+    let (width, height) = (image[0].len(), image.len());
+    let mut grid = vec![vec![false; width + 2 * margin]; height + 2 * margin];
+    for (y, row) in image.iter().enumerate() {
+        grid[y + margin][margin..margin + width].copy_from_slice(row);
+    }
+
+    for _ in 0..steps {
+        grid = (0..grid.len())
+            .map(|y| {
+                (0..grid[y].len())
+                    .map(|x| {
+                        let idx = iproduct!(0..3, 0..3).fold(0, |acc, (dy, dx)| {
+                            let lit = (y + dy)
+                                .checked_sub(1)
+                                .zip((x + dx).checked_sub(1))
+                                .and_then(|(y, x)| grid.get(y)?.get(x))
+                                .is_some_and(|&it| it);
+                            (acc << 1) | usize::from(lit)
+                        });
+                        algo[idx]
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+    }
+
+    let from = margin - steps;
+    grid[from..from + height + 2 * steps]
+        .iter()
+        .flat_map(|row| &row[from..from + width + 2 * steps])
+        .filter(|it| **it)
+        .count()
+}
+
+// Every universe is played out, without memoising repeated game states.
+pub fn day21(positions: [u64; 2], winning_score: u64) -> [u64; 2] {
+    fn play(
+        positions: [u64; 2],
+        scores: [u64; 2],
+        player: usize,
+        winning_score: u64,
+        wins: &mut [u64; 2],
+    ) {
+        for (a, b, c) in iproduct!(1..=3, 1..=3, 1..=3) {
+            let (mut positions, mut scores) = (positions, scores);
+            positions[player] = (positions[player] + a + b + c - 1) % 10 + 1;
+            scores[player] += positions[player];
+
+            if scores[player] >= winning_score {
+                wins[player] += 1;
+            } else {
+                play(positions, scores, 1 - player, winning_score, wins);
+            }
+        }
+    }
+
+    let mut wins = [0; 2];
+    play(positions, [0; 2], 0, winning_score, &mut wins);
+    wins
+}
+
+// Every lit cube is stored.
+pub fn day22(inp: &str) -> usize {
+    let mut lights = HashSet::new();
+
+    for line in inp.lines() {
+        let (flag, cuboid) = line.split_once(' ').expect("Has a flag");
+        let ranges = cuboid
+            .split(',')
+            .map(|it| {
+                let (from, to) = it[2..].split_once("..").expect("Is a range");
+                from.parse::<i64>().expect("Valid bound")..=to.parse().expect("Valid bound")
+            })
+            .collect_vec();
+
+        for cube in iproduct!(ranges[0].clone(), ranges[1].clone(), ranges[2].clone()) {
+            if flag == "on" {
+                lights.insert(cube);
+            } else {
+                lights.remove(&cube);
+            }
+        }
+    }
+
+    lights.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day06, day14, day20, day21, day22, gen};
+    use aoc_utils::count::Count;
+    use aoc_utils::rng::Rng;
+
+    #[test]
+    fn test_day06_matches_reference() {
+        let mut rng = Rng::new(6);
+        for seed in 0..10 {
+            let inp = gen::day06(seed);
+            let num_days = rng.between(0..=80) as u16;
+
+            let Ok(fish) = day06::generate(&inp) else {
+                panic!("Could not parse generated input")
+            };
+            assert_eq!(
+                day06::simulate_fish(num_days, &fish),
+                Ok(Count::from(day06(&inp, num_days) as u64)),
+                "seed {seed}, {num_days} days"
+            );
+        }
+    }
+
+    #[test]
+    fn test_day14_matches_reference() {
+        let mut rng = Rng::new(14);
+        for seed in 0..10 {
+            let inp = gen::day14(seed);
+            let steps = rng.between(0..=10);
+
+            let Ok(gen) = day14::generate(&inp) else {
+                panic!("Could not parse generated input")
+            };
+            assert_eq!(
+                day14::run_steps(steps, &gen),
+                Ok(Count::from(day14(&inp, steps) as u64)),
+                "seed {seed}, {steps} steps"
+            );
+        }
+    }
+
+    #[test]
+    fn test_day20_matches_reference() {
+        let mut rng = Rng::new(20);
+        for seed in 0..10 {
+            let inp = gen::day20(seed);
+            let steps = rng.between(0..=6);
+
+            let Ok(gen) = day20::generate(&inp) else {
+                panic!("Could not parse generated input")
+            };
+            assert_eq!(
+                day20::enhance(&gen, steps),
+                day20(&inp, steps),
+                "seed {seed}, {steps} steps"
+            );
+        }
+    }
+
+    #[test]
+    fn test_day21_matches_reference() {
+        let mut rng = Rng::new(21);
+        for _ in 0..10 {
+            let positions = [rng.between(1..=10) as u64, rng.between(1..=10) as u64];
+            let winning_score = rng.between(1..=8) as u64;
+
+            assert_eq!(
+                day21::play_all_games(positions, winning_score),
+                Ok(day21(positions, winning_score).map(Count::from)),
+                "{positions:?} to {winning_score}"
+            );
+        }
+    }
+
+    fn small_cuboids(rng: &mut Rng) -> String {
+        (0..rng.between(1..=10))
+            .map(|idx| {
+                let flag = if idx == 0 || rng.chance(60) {
+                    "on"
+                } else {
+                    "off"
+                };
+                let mut range = || {
+                    let from = rng.range(-10..=10);
+                    format!("{from}..{}", from + rng.range(0..=8))
+                };
+                format!("{flag} x={},y={},z={}", range(), range(), range())
+            })
+            .join("\n")
+    }
+
+    #[test]
+    fn test_day22_matches_reference() {
+        let mut rng = Rng::new(22);
+        for _ in 0..20 {
+            let inp = small_cuboids(&mut rng);
+
+            let Ok(gen) = day22::generate(&inp) else {
+                panic!("Could not parse generated input")
+            };
+            assert_eq!(
+                usize::try_from(day22::part2(&gen)).ok(),
+                Some(day22(&inp)),
+                "{inp}"
+            );
+        }
+    }
+}
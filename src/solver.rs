@@ -0,0 +1,111 @@
+pub use aoc_utils::solver::{Answer, Error, IntoAnswer, Puzzle};
+
+pub const YEAR: u16 = 2021;
+
+static PUZZLES: &[Puzzle] = aoc_utils::puzzles! {
+    year = 2021;
+    1 => day01 [part1 = 1, part2 = 2],
+    2 => day02 [part1 = 1, part2 = 2],
+    3 => day03 [part1 = 1, part2 = 2],
+    4 => day04 [part1 = 1, part2 = 2],
+    5 => day05 [part1 = 1, part2 = 2],
+    6 => day06 [part1 = 1, part2 = 2],
+    7 => day07 [part1 = 1, part2 = 2],
+    8 => day08 [part1 = 1, part2 = 2],
+    9 => day09 [part1 = 1, part2 = 2],
+    10 => day10 [part1 = 1, part2 = 2],
+    11 => day11 [part1 = 1, part2 = 2],
+    12 => day12 [part1 = 1, part2 = 2],
+    13 => day13 [part1 = 1, part2 = 2],
+    14 => day14 [part1 = 1, part2 = 2],
+    15 => day15 [part1 = 1, part2 = 2],
+    16 => day16 [part1 = 1, part2 = 2],
+    17 => day17 [part1 = 1, part2 = 2],
+    18 => day18 [part1 = 1, part2 = 2],
+    19 => day19 [part1 = 1, part2 = 2],
+    20 => day20 [part1 = 1, part2 = 2],
+    21 => day21 [part1 = 1, part2 = 2],
+    22 => day22 [part1 = 1, part2 = 2],
+    23 => day23 [part1 = 1, part2 = 2],
+    24 => day24 [part1 = 1, part2 = 2],
+    25 => day25 [part1 = 1],
+};
+
+pub fn puzzles() -> &'static [Puzzle] {
+    PUZZLES
+}
+
+pub fn find(year: u16, day: u8, part: u8) -> Option<&'static Puzzle> {
+    PUZZLES
+        .iter()
+        .find(|it| it.year == year && it.day == day && it.part == part)
+}
+
+pub fn solve(year: u16, day: u8, part: u8, input: &str) -> Result<Answer, Error> {
+    find(year, day, part)
+        .ok_or(Error::UnknownPuzzle { year, day, part })?
+        .solve(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_utils::parse::ParseError;
+
+    #[test]
+    fn test_registry_lists_every_part() {
+        assert_eq!(puzzles().len(), 49);
+        assert!(find(YEAR, 25, 1).is_some());
+        assert!(find(YEAR, 25, 2).is_none());
+    }
+
+    #[test]
+    fn test_solve_sample() {
+        let inp = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+        assert_eq!(solve(YEAR, 1, 1, inp), Ok(Answer::Number(7)));
+        assert_eq!(solve(YEAR, 1, 2, inp), Ok(Answer::Number(5)));
+    }
+
+    #[test]
+    fn test_parse_skips_solving() {
+        let Some(puzzle) = find(YEAR, 16, 1) else {
+            panic!("Day 16 is registered")
+        };
+
+        assert_eq!(puzzle.parse("D2FE28"), Ok(()));
+        assert_eq!(
+            puzzle.parse("D2FE"),
+            Err(ParseError::new(16, 1, 5, "a literal value group"))
+        );
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        assert_eq!(
+            solve(YEAR, 1, 1, "199\nx"),
+            Err(Error::Parse(ParseError::new(
+                1,
+                2,
+                1,
+                "a depth measurement"
+            )))
+        );
+        assert_eq!(
+            solve(2020, 1, 1, "199"),
+            Err(Error::UnknownPuzzle {
+                year: 2020,
+                day: 1,
+                part: 1
+            })
+        );
+        assert_eq!(
+            solve(YEAR, 7, 1, ""),
+            Err(Error::Parse(ParseError::new(
+                7,
+                1,
+                1,
+                "a comma-separated list"
+            )))
+        );
+    }
+}
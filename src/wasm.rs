@@ -0,0 +1,15 @@
+// Build the module with
+// `cargo rustc --lib --crate-type cdylib --target wasm32-unknown-unknown --features wasm`.
+use wasm_bindgen::prelude::*;
+
+use crate::solver::{self, Error, YEAR};
+
+// Exported to JavaScript as `solve(day, part, input)`, throwing on bad input.
+#[wasm_bindgen(js_name = solve)]
+pub fn solve_js(day: u8, part: u8, input: &str) -> Result<String, JsError> {
+    solve(day, part, input).map_err(|err| JsError::new(&err.to_string()))
+}
+
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, Error> {
+    Ok(solver::solve(YEAR, day, part, input)?.to_string())
+}
@@ -0,0 +1,124 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use aoc_2021::solver::IntoAnswer;
+use aoc_2021::{day01, day02, day03, day05, day22, solve, Answer};
+
+const MANIFEST: &str = include_str!("../input/2021/answers.toml");
+
+fn expected_answer(day: u8, part: u8) -> Option<Answer> {
+    let manifest = MANIFEST
+        .parse::<toml::Table>()
+        .expect("Manifest is valid TOML");
+    let value = manifest
+        .get(&format!("day{day}"))?
+        .get(format!("part{part}"))?;
+
+    match value {
+        toml::Value::Integer(n) => Some(Answer::Number(*n)),
+        toml::Value::String(s) => Some(Answer::Text(s.clone())),
+        _ => panic!("Unexpected answer type for day {day}, part {part}"),
+    }
+}
+
+fn input_path(day: u8) -> String {
+    format!("{}/input/2021/day{day}.txt", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn check_day(day: u8) {
+    let input = std::fs::read_to_string(input_path(day)).expect("Input file exists");
+
+    for part in 1..=2 {
+        let Some(expected) = expected_answer(day, part) else {
+            continue;
+        };
+
+        let res = solve(2021, day, part, &input);
+        assert_eq!(res, Ok(expected), "day {day}, part {part}");
+    }
+}
+
+macro_rules! answer_tests {
+    ($($name:ident => $day:literal $(, $ignore:literal)?;)*) => {
+        $(
+            #[test]
+            $(#[ignore = $ignore])?
+            fn $name() {
+                check_day($day);
+            }
+        )*
+    };
+}
+
+answer_tests! {
+    day01 => 1;
+    day02 => 2;
+    day03 => 3;
+    day04 => 4;
+    day05 => 5;
+    day06 => 6;
+    day07 => 7;
+    day08 => 8;
+    day09 => 9;
+    day10 => 10;
+    day11 => 11;
+    day12 => 12;
+    day13 => 13;
+    day14 => 14;
+    day15 => 15;
+    day16 => 16;
+    day17 => 17;
+    day18 => 18;
+    day19 => 19, "slow without optimizations, run with --release -- --include-ignored";
+    day20 => 20;
+    day21 => 21;
+    day22 => 22, "slow without optimizations, run with --release -- --include-ignored";
+    day23 => 23;
+    day24 => 24, "slow without optimizations, run with --release -- --include-ignored";
+    day25 => 25;
+}
+
+#[test]
+fn manifest_covers_every_puzzle() {
+    for puzzle in aoc_2021::solver::puzzles() {
+        assert!(
+            expected_answer(puzzle.day, puzzle.part).is_some(),
+            "day {}, part {} has no recorded answer",
+            puzzle.day,
+            puzzle.part
+        );
+    }
+}
+
+fn open_input(day: u8) -> BufReader<File> {
+    BufReader::new(File::open(input_path(day)).expect("Input file exists"))
+}
+
+macro_rules! check_streaming {
+    ($day:literal, $module:ident) => {
+        let res = $module::part1_streaming(open_input($day)).ok();
+        assert_eq!(
+            res.and_then(|it| it.into_answer().ok()),
+            expected_answer($day, 1),
+            "day {}, part 1",
+            $day
+        );
+
+        let res = $module::part2_streaming(open_input($day)).ok();
+        assert_eq!(
+            res.and_then(|it| it.into_answer().ok()),
+            expected_answer($day, 2),
+            "day {}, part 2",
+            $day
+        );
+    };
+}
+
+#[test]
+fn streaming_matches_answers() {
+    check_streaming!(1, day01);
+    check_streaming!(2, day02);
+    check_streaming!(3, day03);
+    check_streaming!(5, day05);
+    check_streaming!(22, day22);
+}
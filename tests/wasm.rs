@@ -0,0 +1,54 @@
+// Also runs under wasmtime, see `.cargo/config.toml`.
+use aoc_2021::wasm;
+
+#[test]
+fn test_solves_sample() {
+    let inp = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+    assert_eq!(wasm::solve(1, 1, inp).as_deref(), Ok("7"));
+    assert_eq!(wasm::solve(1, 2, inp).as_deref(), Ok("5"));
+}
+
+#[test]
+fn test_solves_text_answers() {
+    let inp = "6,10\n\
+               0,14\n\
+               9,10\n\
+               0,3\n\
+               10,4\n\
+               4,11\n\
+               6,0\n\
+               6,12\n\
+               4,1\n\
+               0,13\n\
+               10,12\n\
+               3,4\n\
+               3,0\n\
+               8,4\n\
+               1,10\n\
+               2,14\n\
+               8,10\n\
+               9,0\n\
+               \n\
+               fold along y=7\n\
+               fold along x=5";
+    let Ok(answer) = wasm::solve(13, 2, inp) else {
+        panic!("Could not solve day 13")
+    };
+    assert!(answer.contains('#'));
+}
+
+#[test]
+fn test_reports_errors() {
+    let Err(err) = wasm::solve(26, 1, "") else {
+        panic!("Day 26 does not exist")
+    };
+    assert_eq!(err.to_string(), "no solution for 2021 day 26, part 1");
+
+    let Err(err) = wasm::solve(6, 1, "3,x") else {
+        panic!("Input is malformed")
+    };
+    assert_eq!(
+        err.to_string(),
+        "day 6, line 1, column 3: expected a timer from 0 to 8"
+    );
+}